@@ -1,4 +1,5 @@
-use rust_console_game_engine::{RustConsole, RustConsoleGame, RustConsoleGameEngine};
+use rust_console_game_engine::{DrawTarget, RustConsole, RustConsoleGame, RustConsoleGameEngine};
+use std::error::Error;
 use std::f32;
 
 struct Fps {
@@ -16,7 +17,7 @@ struct Fps {
 impl RustConsoleGame for Fps {
     fn name(&self) -> &str { "FPS" }
 
-    fn setup(&mut self) {
+    fn setup(&mut self) -> Result<(), Box<dyn Error>> {
         self.player_x = 14.7f32;
         self.player_y = 5.09f32;
         self.map_width = 16;
@@ -37,18 +38,23 @@ impl RustConsoleGame for Fps {
 	                             #......#########\
 	                             #..............#\
                                  ################");
+        Ok(())
     }
 
-    fn update(&mut self, console: &mut RustConsole, elapsed_time: f32) {
+    fn update(&mut self, console: &mut RustConsole, elapsed_time: f32) -> Result<(), Box<dyn Error>> {
+        if console.key(RustConsole::VK_ESCAPE as usize).released {
+            console.quit();
+        }
+
         if console.key('H' as usize).released {
             if console.width() == 120 {
-                console.resize(180, 60, 5, 11);
+                console.resize(180, 60, 5, 11)?;
             } else if console.width() == 180 {
-                console.resize(320, 240, 4, 4);
+                console.resize(320, 240, 4, 4)?;
             } else if console.width() == 320 {
-                console.resize(60, 20, 16, 32);
+                console.resize(60, 20, 16, 32)?;
             } else {
-                console.resize(120, 40, 8, 16);
+                console.resize(120, 40, 8, 16)?;
             }
         }
 
@@ -107,7 +113,10 @@ impl RustConsoleGame for Fps {
                 self.player_y += self.player_a.sin() * self.speed * elapsed_time;
             }
         }
+        Ok(())
+    }
 
+    fn render(&mut self, console: &mut RustConsole, _alpha: f32) -> Result<(), Box<dyn Error>> {
         for x in 0..console.width() {
             let ray_angle = (self.player_a - self.fov / 2f32) + (x as f32 / console.width() as f32) * self.fov;
 
@@ -178,9 +187,9 @@ impl RustConsoleGame for Fps {
 
             for y in 0..console.height() {
                 if (y as i32) <= ceiling {
-                    console.draw(x, y, ' ', RustConsole::FG_WHITE);
+                    console.draw(x as i32, y as i32, ' ', RustConsole::FG_WHITE);
                 } else if y as i32 > ceiling && y as i32 <= floor {
-                    console.draw(x, y, shade, RustConsole::FG_WHITE);
+                    console.draw(x as i32, y as i32, shade, RustConsole::FG_WHITE);
                 } else {
                     let b = 1f32 - ((y as f32 - console.height() as f32 / 2f32) / (console.height() as f32 / 2f32));
                     let floor_shade = if b < 0.25f32 {
@@ -194,7 +203,7 @@ impl RustConsoleGame for Fps {
                     } else {
                         ' '
                     };
-                    console.draw(x, y, floor_shade, RustConsole::FG_WHITE);
+                    console.draw(x as i32, y as i32, floor_shade, RustConsole::FG_WHITE);
                 }
             }
         }
@@ -203,7 +212,7 @@ impl RustConsoleGame for Fps {
 
         for mx in 0..self.map_width as usize {
             for my in 0..self.map_height as usize {
-                console.draw(mx, my + 1, self.map.as_bytes()[my * self.map_width as usize + mx] as char, RustConsole::FG_WHITE);
+                console.draw(mx as i32, my as i32 + 1, self.map.as_bytes()[my * self.map_width as usize + mx] as char, RustConsole::FG_WHITE);
             }
         }
 
@@ -220,24 +229,51 @@ impl RustConsoleGame for Fps {
         } else {
             '*'
         };
-        console.draw(self.player_y as usize, self.player_x as usize + 1, p, RustConsole::FG_WHITE);
+        console.draw(self.player_y as i32, self.player_x as i32 + 1, p, RustConsole::FG_WHITE);
+        Ok(())
+    }
+}
+
+impl Fps {
+    fn new() -> Fps {
+        Fps {
+            player_x: 0f32,
+            player_y: 0f32,
+            player_a: 0f32,
+            speed: 5f32,
+            fov: f32::consts::PI / 4f32,
+            depth: 16f32,
+            map: String::default(),
+            map_width: 0,
+            map_height: 0
+        }
     }
 }
 
 fn main() {
-    let mut game = Fps {
-        player_x: 0f32,
-        player_y: 0f32,
-        player_a: 0f32,
-        speed: 5f32,
-        fov: f32::consts::PI / 4f32,
-        depth: 16f32,
-        map: String::default(),
-        map_width: 0,
-        map_height: 0
-    };
+    let mut game = Fps::new();
     let mut engine = RustConsoleGameEngine::new(&mut game, 120, 40, 8, 16).unwrap_or_else(|error| {
         panic!("Error creating console: {:?}", error);
     });
-    engine.run();
+    engine.set_fixed_timestep(120f32, 8).unwrap_or_else(|error| {
+        panic!("Error setting the timestep: {:?}", error);
+    });
+    engine.run().unwrap_or_else(|error| {
+        panic!("Error running game: {:?}", error);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_console_game_engine::{assert_snapshot, run_frames};
+
+    #[test]
+    fn snapshot() {
+        let mut game = Fps::new();
+        let mut keys = vec![vec!['W' as usize]; 10];
+        keys.extend(vec![vec!['A' as usize]; 5]);
+        let frame = run_frames(&mut game, 60, 30, 20, 1f32 / 30f32, keys);
+        assert_snapshot(&frame, concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/fps.snap"));
+    }
 }
@@ -1,4 +1,6 @@
-use rust_console_game_engine::{RustConsole, RustConsoleGame, RustConsoleGameEngine, RustConsoleSprite};
+use rust_console_game_engine::{DrawTarget, RustConsole, RustConsoleGame, RustConsoleGameEngine, RustConsoleSprite};
+
+use std::error::Error;
 
 struct Mode7 {
     world_x: f32,
@@ -14,9 +16,12 @@ struct Mode7 {
 impl RustConsoleGame for Mode7 {
     fn name(&self) -> &str { "Mode7" }
 
-    fn setup(&mut self) {}
+    fn setup(&mut self) -> Result<(), Box<dyn Error>> { Ok(()) }
 
-    fn update(&mut self, console: &mut RustConsole, elapsed_time: f32) {
+    fn update(&mut self, console: &mut RustConsole, elapsed_time: f32) -> Result<(), Box<dyn Error>> {
+        if console.key(RustConsole::VK_ESCAPE as usize).released {
+            console.quit();
+        }
 
         // control rendering params dynamically
         if console.key('Q' as usize).held {
@@ -79,18 +84,18 @@ impl RustConsoleGame for Mode7 {
                 // pixel to the screen
                 let mut sym = self.sprite_ground.sample_glyph(sample_x, sample_y);
                 let mut col = self.sprite_ground.sample_color(sample_x, sample_y);
-                console.draw(x, y + (console.height() / 2), sym, col);
+                console.draw(x as i32, (y + console.height() / 2) as i32, sym, col);
 
                 // sample symbol and color from sky sprite, we can use same
                 // coord, but we need to draw the "inverted" y-location
                 sym = self.sprite_sky.sample_glyph(sample_x, sample_y);
                 col = self.sprite_sky.sample_color(sample_x, sample_y);
-                console.draw(x, (console.height() / 2) - y, sym, col);
+                console.draw(x as i32, (console.height() / 2 - y) as i32, sym, col);
             }
         }
 
         // draw a blanking line to fill gap between sky and ground
-        console.draw_line(0, console.height() / 2, console.width(), console.height() / 2, RustConsole::PIXEL_SOLID, RustConsole::FG_CYAN);
+        console.draw_line(0, console.height() as i32 / 2, console.width() as i32, console.height() as i32 / 2, RustConsole::PIXEL_SOLID, RustConsole::FG_CYAN);
 
         // handle navigation with arrow keys
         if console.key(RustConsole::VK_LEFT as usize).held {
@@ -110,26 +115,68 @@ impl RustConsoleGame for Mode7 {
             self.world_x -= self.world_a.cos() * 0.2f32 * elapsed_time;
             self.world_y -= self.world_a.sin() * 0.2f32 * elapsed_time;
         }
+        Ok(())
+    }
+}
+
+impl Mode7 {
+    fn new(sprite_ground: RustConsoleSprite, sprite_sky: RustConsoleSprite) -> Mode7 {
+        Mode7 {
+            world_x: 1000f32,
+            world_y: 1000f32,
+            world_a: 0.1f32,
+            near: 0.005f32,
+            far: 0.03f32,
+            fov_half: 3.14159f32 / 4f32,
+            sprite_ground,
+            sprite_sky
+        }
     }
 }
 
 fn main() {
-    let mut game = Mode7 {
-        world_x: 1000f32,
-        world_y: 1000f32,
-        world_a: 0.1f32,
-        near: 0.005f32,
-        far: 0.03f32,
-        fov_half: 3.14159f32 / 4f32,
-        sprite_ground: RustConsoleSprite::from_path("assets/mariokart.spr").unwrap_or_else(|error| {
+    let mut game = Mode7::new(
+        RustConsoleSprite::from_path("assets/mariokart.spr").unwrap_or_else(|error| {
             panic!("Error loading sprite: {:?}", error);
         }),
-        sprite_sky: RustConsoleSprite::from_path("assets/sky1.spr").unwrap_or_else(|error| {
+        RustConsoleSprite::from_path("assets/sky1.spr").unwrap_or_else(|error| {
             panic!("Error loading sprite: {:?}", error);
         })
-    };
+    );
     let mut engine = RustConsoleGameEngine::new(&mut game, 320, 240, 4, 4).unwrap_or_else(|error| {
         panic!("Error creating console: {:?}", error);
     });
-    engine.run();
-}
\ No newline at end of file
+    engine.run().unwrap_or_else(|error| {
+        panic!("Error running game: {:?}", error);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_console_game_engine::{assert_snapshot, run_frames};
+
+    // the assets are not checked in, so the test uses generated checkerboards
+    fn checkerboard(size: usize, cell: usize, c: char, col1: u16, col2: u16) -> RustConsoleSprite {
+        let mut sprite = RustConsoleSprite::new(size, size).unwrap();
+        for x in 0..size {
+            for y in 0..size {
+                sprite.set_glyph(x, y, c);
+                sprite.set_color(x, y, if (x / cell + y / cell) % 2 == 0 { col1 } else { col2 });
+            }
+        }
+        sprite
+    }
+
+    #[test]
+    fn snapshot() {
+        let mut game = Mode7::new(
+            checkerboard(64, 8, RustConsole::PIXEL_SOLID, RustConsole::FG_GREEN, RustConsole::FG_DARK_GREEN),
+            checkerboard(64, 16, RustConsole::PIXEL_HALF, RustConsole::FG_BLUE, RustConsole::FG_CYAN)
+        );
+        let mut keys = vec![vec![RustConsole::VK_UP as usize]; 10];
+        keys.extend(vec![vec![RustConsole::VK_LEFT as usize]; 5]);
+        let frame = run_frames(&mut game, 64, 40, 15, 1f32 / 30f32, keys);
+        assert_snapshot(&frame, concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/mode7.snap"));
+    }
+}
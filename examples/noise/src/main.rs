@@ -1,27 +1,56 @@
-use rust_console_game_engine::{RustConsole, RustConsoleGame, RustConsoleGameEngine};
+use rust_console_game_engine::{DrawTarget, RustConsole, RustConsoleGame, RustConsoleGameEngine};
 use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
 
-struct Noise {}
+use std::error::Error;
+
+struct Noise {
+    rng: ChaCha8Rng
+}
 
 impl RustConsoleGame for Noise {
     fn name(&self) -> &str { "Noise" }
 
-    fn setup(&mut self) {}
+    fn setup(&mut self) -> Result<(), Box<dyn Error>> { Ok(()) }
+
+    fn update(&mut self, console: &mut RustConsole, _elapsed_time: f32) -> Result<(), Box<dyn Error>> {
+        if console.key(RustConsole::VK_ESCAPE as usize).released {
+            console.quit();
+        }
 
-    fn update(&mut self, console: &mut RustConsole, _elapsed_time: f32) {
         for x in 0..console.width() {
             for y in 0..console.height() {
-                let random: u16 = random();
-                console.draw(x, y, '#', random % 16);
+                let random: u16 = self.rng.gen();
+                console.draw(x as i32, y as i32, '#', random % 16);
             }
         }
+        Ok(())
     }
 }
 
 fn main() {
-    let mut game = Noise {};
+    let mut game = Noise {
+        rng: ChaCha8Rng::from_entropy()
+    };
     let mut engine = RustConsoleGameEngine::new(&mut game, 160, 100, 8, 8).unwrap_or_else(|error| {
         panic!("Error creating console: {:?}", error);
     });
-    engine.run();
-}
\ No newline at end of file
+    engine.run().unwrap_or_else(|error| {
+        panic!("Error running game: {:?}", error);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_console_game_engine::{assert_snapshot, run_frames};
+
+    #[test]
+    fn snapshot() {
+        let mut game = Noise {
+            rng: ChaCha8Rng::seed_from_u64(42)
+        };
+        let frame = run_frames(&mut game, 32, 12, 3, 1f32 / 60f32, Vec::new());
+        assert_snapshot(&frame, concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/noise.snap"));
+    }
+}
@@ -1,6 +1,7 @@
-use rust_console_game_engine::{RustConsole, RustConsoleGame, RustConsoleGameEngine};
+use rust_console_game_engine::{DrawTarget, RustConsole, RustConsoleGame, RustConsoleGameEngine};
 
 use std::collections::VecDeque;
+use std::error::Error;
 
 struct Racer {
     car_pos: f32,
@@ -12,13 +13,15 @@ struct Racer {
     track_distance: f32,
     current_lap_time: f32,
     track: Vec<(f32, f32)>, // (curvature, distance)
+    track_section: usize,
+    car_direction: i32,
     lap_times: VecDeque<f32>
 }
 
 impl RustConsoleGame for Racer {
     fn name(&self) -> &str { "Racer" }
 
-    fn setup(&mut self) {
+    fn setup(&mut self) -> Result<(), Box<dyn Error>> {
         self.track.push((0f32, 10f32)); // short section to start/finish line
         self.track.push((0f32, 200f32));
         self.track.push((1f32, 200f32));
@@ -34,27 +37,32 @@ impl RustConsoleGame for Racer {
         self.track_distance = self.track.iter().fold(self.track_distance, |td, seg| td + seg.1);
 
         self.lap_times = vec![0f32; 5].into_iter().collect();
+        Ok(())
     }
 
-    fn update(&mut self, console: &mut RustConsole, elapsed_time: f32) {
+    fn update(&mut self, console: &mut RustConsole, elapsed_time: f32) -> Result<(), Box<dyn Error>> {
+        if console.key(RustConsole::VK_ESCAPE as usize).released {
+            console.quit();
+        }
+
         if console.key(RustConsole::VK_UP as usize).held {
             self.speed += 2f32 * elapsed_time;
         } else {
             self.speed -= 1f32 * elapsed_time;
         }
 
-        let mut car_direction = 0;
+        self.car_direction = 0;
 
         // car curvature is accumulated left/right input, but inversely proportional to speed
         // i.e. it is harder to turn at high speed
         if console.key(RustConsole::VK_LEFT as usize).held {
             self.player_curvature -= 0.7f32 * elapsed_time * (1f32 - self.speed / 2f32);
-            car_direction = -1;
+            self.car_direction = -1;
         }
 
         if console.key(RustConsole::VK_RIGHT as usize).held {
             self.player_curvature += 0.7f32 * elapsed_time * (1f32 - self.speed / 2f32);
-            car_direction = 1;
+            self.car_direction = 1;
         }
 
         // if car curvature is too different to track curvature, slow down
@@ -76,7 +84,7 @@ impl RustConsoleGame for Racer {
 
         // get point on track
         let mut offset = 0f32;
-        let mut track_section = 0;
+        self.track_section = 0;
 
         // lap timing and counting
         self.current_lap_time += elapsed_time;
@@ -88,13 +96,13 @@ impl RustConsoleGame for Racer {
         }
 
         // find position on track
-        while track_section < self.track.len() && offset <= self.distance {
-            offset += self.track[track_section].1;
-            track_section += 1;
+        while self.track_section < self.track.len() && offset <= self.distance {
+            offset += self.track[self.track_section].1;
+            self.track_section += 1;
         }
 
         // interpolate towards target track curvature
-        let target_curvature = self.track[track_section - 1].0;
+        let target_curvature = self.track[self.track_section - 1].0;
         let track_curve_diff = (target_curvature - self.curvature) * elapsed_time * self.speed;
         
         // accumulate player curvature
@@ -102,11 +110,14 @@ impl RustConsoleGame for Racer {
 
         // accumulate track curvature
         self.track_curvature += self.curvature * elapsed_time * self.speed;
+        Ok(())
+    }
 
+    fn render(&mut self, console: &mut RustConsole, _alpha: f32) -> Result<(), Box<dyn Error>> {
         // draw sky - light blue and dark blue
         for y in 0..console.height() / 2 {
             for x in 0..console.width() {
-                console.draw(x, y, if y < console.height() / 4 { RustConsole::PIXEL_HALF } else { RustConsole::PIXEL_SOLID }, RustConsole::FG_DARK_BLUE);
+                console.draw(x as i32, y as i32, if y < console.height() / 4 { RustConsole::PIXEL_HALF } else { RustConsole::PIXEL_SOLID }, RustConsole::FG_DARK_BLUE);
             }
         }
 
@@ -115,7 +126,7 @@ impl RustConsoleGame for Racer {
         for x in 0..console.width() {
             let hill_height = ((x as f32 * 0.01f32 + self.track_curvature).sin() * 16f32).abs() as isize;
             for y in ((console.height() / 2) as isize - hill_height) as usize..console.height() / 2 {
-                console.draw(x, y, RustConsole::PIXEL_SOLID, RustConsole::FG_DARK_YELLOW);
+                console.draw(x as i32, y as i32, RustConsole::PIXEL_SOLID, RustConsole::FG_DARK_YELLOW);
             }
         }
 
@@ -145,19 +156,20 @@ impl RustConsoleGame for Racer {
                 let grass_color = if (20f32 * (1f32 - perspective).powi(3) + self.distance * 0.1f32).sin() > 0f32 { RustConsole::FG_GREEN } else { RustConsole::FG_DARK_GREEN };
                 let clip_color = if (80f32 * (1f32 - perspective).powi(2) + self.distance).sin() > 0f32 { RustConsole::FG_RED } else { RustConsole::FG_WHITE };
 
-                let road_color = if track_section - 1 == 0 { RustConsole::FG_WHITE } else { RustConsole::FG_GREY };
+                // the first section is the start line, render can also run before the first update has found a section
+                let road_color = if self.track_section <= 1 { RustConsole::FG_WHITE } else { RustConsole::FG_GREY };
 
                 // draw the row segments
                 if (x as f32) < left_grass {
-                    console.draw(x, row, RustConsole::PIXEL_SOLID, grass_color);
+                    console.draw(x as i32, row as i32, RustConsole::PIXEL_SOLID, grass_color);
                 } else if (x as f32) >= left_grass && (x as f32) < left_clip {
-                    console.draw(x, row, RustConsole::PIXEL_SOLID, clip_color);
+                    console.draw(x as i32, row as i32, RustConsole::PIXEL_SOLID, clip_color);
                 } else if (x as f32) >= left_clip && (x as f32) < right_clip {
-                    console.draw(x, row, RustConsole::PIXEL_SOLID, road_color);
+                    console.draw(x as i32, row as i32, RustConsole::PIXEL_SOLID, road_color);
                 } else if (x as f32) >= right_clip && (x as f32) < right_grass {
-                    console.draw(x, row, RustConsole::PIXEL_SOLID, clip_color);
+                    console.draw(x as i32, row as i32, RustConsole::PIXEL_SOLID, clip_color);
                 } else if (x as f32) >= right_grass && x < console.width() {
-                    console.draw(x, row, RustConsole::PIXEL_SOLID, grass_color);
+                    console.draw(x as i32, row as i32, RustConsole::PIXEL_SOLID, grass_color);
                 }
             }
         }
@@ -166,9 +178,9 @@ impl RustConsoleGame for Racer {
 		// current accumulated track curvature, and current accumulated player curvature
 		// i.e. if they are similar, the car will be in the middle of the track
         self.car_pos = self.player_curvature - self.track_curvature;
-        let car_pos = (console.width() as isize / 2 + ((console.width() as f32 * self.car_pos / 2.0f32) as isize)) as usize - 7; // offset for sprite
+        let car_pos = console.width() as i32 / 2 + (console.width() as f32 * self.car_pos / 2.0f32) as i32 - 7; // offset for sprite
 
-        if car_direction == 0 {
+        if self.car_direction == 0 {
             console.draw_string_alpha(car_pos, 80, "   ||####||   ", RustConsole::FG_WHITE);
             console.draw_string_alpha(car_pos, 81, "      ##      ", RustConsole::FG_WHITE);
             console.draw_string_alpha(car_pos, 82, "     ####     ", RustConsole::FG_WHITE);
@@ -176,7 +188,7 @@ impl RustConsoleGame for Racer {
             console.draw_string_alpha(car_pos, 84, "|||  ####  |||", RustConsole::FG_WHITE);
             console.draw_string_alpha(car_pos, 85, "|||########|||", RustConsole::FG_WHITE);
             console.draw_string_alpha(car_pos, 86, "|||  ####  |||", RustConsole::FG_WHITE);
-        } else if car_direction == 1 {
+        } else if self.car_direction == 1 {
             console.draw_string_alpha(car_pos, 80, "      //####//", RustConsole::FG_WHITE);
             console.draw_string_alpha(car_pos, 81, "         ##   ", RustConsole::FG_WHITE);
             console.draw_string_alpha(car_pos, 82, "       ####   ", RustConsole::FG_WHITE);
@@ -184,7 +196,7 @@ impl RustConsoleGame for Racer {
             console.draw_string_alpha(car_pos, 84, "///  ####//// ", RustConsole::FG_WHITE);
             console.draw_string_alpha(car_pos, 85, "//#######///O ", RustConsole::FG_WHITE);
             console.draw_string_alpha(car_pos, 86, "/// #### //// ", RustConsole::FG_WHITE);
-        } else if car_direction == -1 {
+        } else if self.car_direction == -1 {
             console.draw_string_alpha(car_pos, 80, "\\\\####\\\\      ", RustConsole::FG_WHITE);
             console.draw_string_alpha(car_pos, 81, "   ##         ", RustConsole::FG_WHITE);
             console.draw_string_alpha(car_pos, 82, "   ####       ", RustConsole::FG_WHITE);
@@ -215,24 +227,70 @@ impl RustConsoleGame for Racer {
             console.draw_string(10, j, disp_time(*l).as_str(), RustConsole::FG_WHITE);
             j += 1;
         }
+        Ok(())
+    }
+}
+
+impl Racer {
+    fn new() -> Racer {
+        Racer {
+            car_pos: 0f32,
+            distance: 0f32,
+            speed: 0f32,
+            curvature: 0f32,
+            track_curvature: 0f32,
+            player_curvature: 0f32,
+            track_distance: 0f32,
+            current_lap_time: 0f32,
+            track: Vec::new(),
+            track_section: 0,
+            car_direction: 0,
+            lap_times: VecDeque::new()
+        }
     }
 }
 
 fn main() {
-    let mut game = Racer {
-        car_pos: 0f32,
-        distance: 0f32,
-        speed: 0f32,
-        curvature: 0f32,
-        track_curvature: 0f32,
-        player_curvature: 0f32,
-        track_distance: 0f32,
-        current_lap_time: 0f32,
-        track: Vec::new(),
-        lap_times: VecDeque::new()
-    };
+    let mut game = Racer::new();
     let mut engine = RustConsoleGameEngine::new(&mut game, 160, 100, 8, 8).unwrap_or_else(|error| {
         panic!("Error creating console: {:?}", error);
     });
-    engine.run();
-}
\ No newline at end of file
+    engine.set_fixed_timestep(120f32, 8).unwrap_or_else(|error| {
+        panic!("Error setting the timestep: {:?}", error);
+    });
+    engine.run().unwrap_or_else(|error| {
+        panic!("Error running game: {:?}", error);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_console_game_engine::{assert_snapshot, HeadlessBackend};
+
+    // with the same fixed timestep as main
+    fn run_fixed(game: &mut Racer, frames: usize, elapsed_time: f32, keys: Vec<Vec<usize>>) -> RustConsoleGameEngine<'_> {
+        let mut engine = RustConsoleGameEngine::with_backend(game, Box::new(HeadlessBackend::with_key_script(keys)), 80, 90, 8, 8).unwrap();
+        engine.set_fixed_timestep(120f32, 8).unwrap();
+        engine.start().unwrap();
+        for _ in 0..frames {
+            engine.step(elapsed_time).unwrap();
+        }
+        engine
+    }
+
+    #[test]
+    fn snapshot() {
+        let mut game = Racer::new();
+        let mut keys = vec![vec![RustConsole::VK_UP as usize]; 30];
+        keys.extend(vec![vec![RustConsole::VK_UP as usize, RustConsole::VK_LEFT as usize]; 10]);
+        let engine = run_fixed(&mut game, 40, 1f32 / 30f32, keys);
+        assert_snapshot(&engine.console().to_sprite(), concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/racer.snap"));
+    }
+
+    #[test]
+    fn renders_before_the_first_update() {
+        let mut game = Racer::new();
+        run_fixed(&mut game, 1, 0.0001f32, Vec::new());
+    }
+}
@@ -0,0 +1,212 @@
+use super::{AnimationError, BlitOptions, Rect, RustConsoleSprite};
+use super::draw::DrawTarget;
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Playback {
+    Loop,
+    // forwards then backwards, the first and last frames are not shown twice in a row
+    PingPong,
+    // stops on the last frame
+    OneShot
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clip {
+    // index into the frames of the animation, and how long it is shown in seconds
+    pub frames: Vec<(usize, f32)>,
+    pub playback: Playback
+}
+
+impl Clip {
+    pub fn new(playback: Playback) -> Clip {
+        Clip { frames: Vec::new(), playback }
+    }
+
+    pub fn frame(mut self, frame: usize, duration: f32) -> Clip {
+        self.frames.push((frame, duration));
+        self
+    }
+
+    pub fn uniform(frames: impl IntoIterator<Item = usize>, duration: f32, playback: Playback) -> Clip {
+        Clip { frames: frames.into_iter().map(|frame| (frame, duration)).collect(), playback }
+    }
+
+    // time after which the clip is back on the same frame, going the same way
+    fn cycle(&self) -> f32 {
+        let total: f32 = self.frames.iter().map(|&(_, duration)| duration).sum();
+        match self.playback {
+            Playback::PingPong if self.frames.len() > 2 => total + self.frames[1..self.frames.len() - 1].iter().map(|&(_, duration)| duration).sum::<f32>(),
+            _ => total
+        }
+    }
+}
+
+pub struct Animation {
+    sheet: RustConsoleSprite,
+    frames: Vec<Rect>,
+    clips: HashMap<String, Clip>
+}
+
+impl Animation {
+    // frames left to right, then top to bottom, cells on the right and bottom edges that don't
+    // make a whole frame are left out
+    pub fn from_grid(sheet: RustConsoleSprite, frame_width: usize, frame_height: usize) -> Result<Animation, AnimationError> {
+        if frame_width == 0 || frame_height == 0 {
+            return Err(AnimationError::InvalidFrameSize { width: frame_width, height: frame_height });
+        }
+        let mut frames = Vec::new();
+        for row in 0..sheet.height() / frame_height {
+            for column in 0..sheet.width() / frame_width {
+                frames.push(Rect::new((column * frame_width) as i32, (row * frame_height) as i32, frame_width as i32, frame_height as i32));
+            }
+        }
+        Ok(Animation::from_rects(sheet, frames))
+    }
+
+    // parts of the rects outside of the sheet are drawn as transparent
+    pub fn from_rects(sheet: RustConsoleSprite, frames: Vec<Rect>) -> Animation {
+        Animation {
+            sheet,
+            frames,
+            clips: HashMap::new()
+        }
+    }
+
+    pub fn sheet(&self) -> &RustConsoleSprite { &self.sheet }
+
+    pub fn frames(&self) -> &[Rect] { &self.frames }
+
+    pub fn clip(&self, name: &str) -> Option<&Clip> { self.clips.get(name) }
+
+    // replaces any clip with the same name
+    pub fn add_clip(&mut self, name: &str, clip: Clip) -> Result<(), AnimationError> {
+        if clip.frames.is_empty() {
+            return Err(AnimationError::EmptyClip(name.to_string()));
+        }
+        for &(frame, duration) in &clip.frames {
+            if frame >= self.frames.len() {
+                return Err(AnimationError::FrameOutOfRange { clip: name.to_string(), frame });
+            }
+            if !duration.is_finite() || duration <= 0f32 {
+                return Err(AnimationError::InvalidDuration { clip: name.to_string(), duration });
+            }
+        }
+        self.clips.insert(name.to_string(), clip);
+        Ok(())
+    }
+}
+
+// the playback state of one clip of a shared animation, so that many things on screen can
+// use the same sheet, each at its own frame
+pub struct AnimatedSprite {
+    animation: Rc<Animation>,
+    clip: Option<String>,
+    // position in the clip, not in the frames of the animation
+    position: usize,
+    forwards: bool,
+    // time spent on the current position
+    time: f32,
+    finished: bool
+}
+
+impl AnimatedSprite {
+    pub fn new(animation: Rc<Animation>) -> AnimatedSprite {
+        AnimatedSprite {
+            animation,
+            clip: None,
+            position: 0,
+            forwards: true,
+            time: 0f32,
+            finished: false
+        }
+    }
+
+    pub fn animation(&self) -> &Rc<Animation> { &self.animation }
+
+    pub fn clip(&self) -> Option<&str> { self.clip.as_deref() }
+
+    // a one shot clip is finished once its last frame has been shown for its whole duration
+    pub fn is_finished(&self) -> bool { self.finished }
+
+    // switching clips starts the new one from its first frame, playing the current clip again
+    // carries on where it is, so this can be called on every update
+    pub fn play(&mut self, name: &str) -> Result<(), AnimationError> {
+        if self.animation.clip(name).is_none() {
+            return Err(AnimationError::UnknownClip(name.to_string()));
+        }
+        if self.clip.as_deref() != Some(name) {
+            self.clip = Some(name.to_string());
+            self.restart();
+        }
+        Ok(())
+    }
+
+    pub fn restart(&mut self) {
+        self.position = 0;
+        self.forwards = true;
+        self.time = 0f32;
+        self.finished = false;
+    }
+
+    // index into the frames of the animation, None until a clip is played
+    pub fn frame(&self) -> Option<usize> {
+        let clip = self.animation.clip(self.clip.as_deref()?)?;
+        Some(clip.frames[self.position].0)
+    }
+
+    pub fn update(&mut self, elapsed_time: f32) {
+        let animation = Rc::clone(&self.animation);
+        let clip = match self.clip.as_deref().and_then(|name| animation.clip(name)) {
+            Some(clip) => clip,
+            None => return
+        };
+        if self.finished || !elapsed_time.is_finite() || elapsed_time <= 0f32 {
+            return;
+        }
+        self.time += elapsed_time;
+        if clip.playback != Playback::OneShot {
+            // a long pause must not step through every frame it missed
+            let cycle = clip.cycle();
+            if self.time >= cycle {
+                self.time %= cycle;
+            }
+        }
+        let last = clip.frames.len() - 1;
+        while self.time >= clip.frames[self.position].1 {
+            self.time -= clip.frames[self.position].1;
+            match clip.playback {
+                Playback::Loop => self.position = if self.position == last { 0 } else { self.position + 1 },
+                Playback::PingPong if last > 0 => {
+                    if self.forwards && self.position == last {
+                        self.forwards = false;
+                    } else if !self.forwards && self.position == 0 {
+                        self.forwards = true;
+                    }
+                    self.position = if self.forwards { self.position + 1 } else { self.position - 1 };
+                },
+                Playback::PingPong => {},
+                Playback::OneShot => {
+                    if self.position == last {
+                        self.time = 0f32;
+                        self.finished = true;
+                        break;
+                    }
+                    self.position += 1;
+                }
+            }
+        }
+    }
+
+    pub fn draw<T: DrawTarget + ?Sized>(&self, target: &mut T, x: i32, y: i32) {
+        self.draw_with(target, x, y, &BlitOptions::default());
+    }
+
+    pub fn draw_with<T: DrawTarget + ?Sized>(&self, target: &mut T, x: i32, y: i32, options: &BlitOptions) {
+        if let Some(frame) = self.frame() {
+            target.draw_partial_sprite(x, y, self.animation.sheet(), self.animation.frames()[frame], options);
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use super::{RustConsoleSprite, SpriteError};
+use super::cp437::cp437_to_char;
+use super::palette::{nearest_color, ANSI_COLORS};
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+// the width of a file without a SAUCE record, or with no width in it
+const DEFAULT_WIDTH: usize = 80;
+const SAUCE_SIZE: usize = 128;
+const COMMENT_SIZE: usize = 64;
+// marks the end of the art, anything after it is metadata
+const EOF: u8 = 0x1a;
+
+// the metadata record at the end of most ansi art, strings are trimmed of their padding
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sauce {
+    pub title: String,
+    pub author: String,
+    pub group: String,
+    // CCYYMMDD
+    pub date: String,
+    pub data_type: u8,
+    pub file_type: u8,
+    // in characters for ansi files, 0 when unknown
+    pub width: u16,
+    pub height: u16,
+    // blink turns the background bright instead of blinking
+    pub ice_colors: bool,
+    pub comments: Vec<String>
+}
+
+impl Sauce {
+    pub fn read(bytes: &[u8]) -> Option<Sauce> {
+        sauce(bytes).map(|(sauce, _)| sauce)
+    }
+}
+
+// the record and where it starts, comments included
+fn sauce(bytes: &[u8]) -> Option<(Sauce, usize)> {
+    if bytes.len() < SAUCE_SIZE {
+        return None;
+    }
+    let start = bytes.len() - SAUCE_SIZE;
+    let record = &bytes[start..];
+    if &record[..7] != b"SAUCE00" {
+        return None;
+    }
+    let u16_at = |i: usize| u16::from_le_bytes([record[i], record[i + 1]]);
+    let count = record[104] as usize;
+    let mut comments = Vec::new();
+    let mut begin = start;
+    // the comment block is dropped if it isn't where the record says
+    if count > 0 && start >= 5 + count * COMMENT_SIZE {
+        let block = start - 5 - count * COMMENT_SIZE;
+        if &bytes[block..block + 5] == b"COMNT" {
+            comments = bytes[block + 5..start].chunks(COMMENT_SIZE).map(text).collect();
+            begin = block;
+        }
+    }
+    Some((Sauce {
+        title: text(&record[7..42]),
+        author: text(&record[42..62]),
+        group: text(&record[62..82]),
+        date: text(&record[82..90]),
+        data_type: record[94],
+        file_type: record[95],
+        width: u16_at(96),
+        height: u16_at(98),
+        ice_colors: record[105] & 1 != 0,
+        comments
+    }, begin))
+}
+
+fn text(bytes: &[u8]) -> String {
+    let end = bytes.iter().rposition(|&b| b != b' ' && b != 0).map_or(0, |i| i + 1);
+    bytes[..end].iter().map(|&b| cp437_to_char(b)).collect()
+}
+
+#[derive(Copy, Clone)]
+struct Pen {
+    fg: u16,
+    bg: u16,
+    // only the 8 base colours get brighter with bold and blink, the others are set as they are
+    fg_base: bool,
+    bg_base: bool,
+    bold: bool,
+    blink: bool,
+    reverse: bool
+}
+
+impl Pen {
+    const DEFAULT: Pen = Pen { fg: 7, bg: 0, fg_base: true, bg_base: true, bold: false, blink: false, reverse: false };
+
+    fn color(&self, ice_colors: bool) -> u16 {
+        let fg = if self.fg_base && self.bold { self.fg | 8 } else { self.fg };
+        let bg = if self.bg_base && self.blink && ice_colors { self.bg | 8 } else { self.bg };
+        let (fg, bg) = if self.reverse { (bg, fg) } else { (fg, bg) };
+        fg | bg << 4
+    }
+
+    fn sgr(&mut self, params: &[Option<u32>]) {
+        let mut i = 0;
+        // an empty sequence is a reset
+        while i < params.len().max(1) {
+            let code = params.get(i).copied().flatten().unwrap_or(0);
+            match code {
+                0 => *self = Pen::DEFAULT,
+                1 => self.bold = true,
+                5 | 6 => self.blink = true,
+                7 => self.reverse = true,
+                22 => self.bold = false,
+                25 => self.blink = false,
+                27 => self.reverse = false,
+                30..=37 => { self.fg = ANSI_COLORS[code as usize - 30]; self.fg_base = true; },
+                39 => { self.fg = Pen::DEFAULT.fg; self.fg_base = true; },
+                40..=47 => { self.bg = ANSI_COLORS[code as usize - 40]; self.bg_base = true; },
+                49 => { self.bg = Pen::DEFAULT.bg; self.bg_base = true; },
+                90..=97 => { self.fg = ANSI_COLORS[code as usize - 90] | 8; self.fg_base = false; },
+                100..=107 => { self.bg = ANSI_COLORS[code as usize - 100] | 8; self.bg_base = false; },
+                38 | 48 => {
+                    let (color, used) = extended_color(&params[i + 1..]);
+                    i += used;
+                    if let Some(color) = color {
+                        if code == 38 {
+                            self.fg = color;
+                            self.fg_base = false;
+                        } else {
+                            self.bg = color;
+                            self.bg_base = false;
+                        }
+                    }
+                },
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+}
+
+// 5;n for the 256 colours of xterm or 2;r;g;b, and how many parameters that took
+fn extended_color(params: &[Option<u32>]) -> (Option<u16>, usize) {
+    let param = |i: usize| params.get(i).copied().flatten().unwrap_or(0);
+    match params.first().copied().flatten() {
+        Some(5) if params.len() >= 2 => {
+            let n = param(1);
+            let color = match n {
+                0..=15 => ANSI_COLORS[n as usize % 8] | if n >= 8 { 8 } else { 0 },
+                16..=231 => {
+                    let level = |v: u32| if v == 0 { 0 } else { (55 + v * 40) as u8 };
+                    let n = n - 16;
+                    nearest_color([level(n / 36), level(n / 6 % 6), level(n % 6)])
+                },
+                232..=255 => {
+                    let grey = (8 + (n - 232) * 10) as u8;
+                    nearest_color([grey, grey, grey])
+                },
+                _ => return (None, 2)
+            };
+            (Some(color), 2)
+        },
+        Some(2) if params.len() >= 4 => {
+            let channel = |i: usize| param(i).min(255) as u8;
+            (Some(nearest_color([channel(1), channel(2), channel(3)])), 4)
+        },
+        _ => (None, params.len())
+    }
+}
+
+// the cells written so far, rows are added as the cursor reaches them
+struct Screen {
+    width: usize,
+    cells: Vec<(char, u16)>
+}
+
+impl Screen {
+    fn row(&mut self, y: usize) -> Result<&mut [(char, u16)], SpriteError> {
+        if y >= RustConsoleSprite::MAX_CELLS / self.width {
+            return Err(SpriteError::TooLarge { width: self.width, height: y + 1 });
+        }
+        if self.cells.len() < (y + 1) * self.width {
+            self.cells.resize((y + 1) * self.width, (' ', 0));
+        }
+        Ok(&mut self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    fn rows(&self) -> usize { self.cells.len() / self.width }
+
+    // blanks the cells of a row in the range, rows that weren't reached yet are blank already
+    fn erase(&mut self, y: usize, from: usize, to: usize) {
+        if y < self.rows() {
+            let width = self.width;
+            self.cells[y * width + from..y * width + to.min(width)].iter_mut().for_each(|cell| *cell = (' ', 0));
+        }
+    }
+}
+
+impl RustConsoleSprite {
+    pub fn from_ans(path: &str) -> Result<RustConsoleSprite, SpriteError> {
+        RustConsoleSprite::from_ans_reader(BufReader::new(File::open(path)?))
+    }
+
+    // cp437 text with the escape sequences of ANSI.SYS, the width comes from the SAUCE record
+    // and the height from the last row that was written to, cells never written to are ' '
+    pub fn from_ans_reader(mut reader: impl Read) -> Result<RustConsoleSprite, SpriteError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let (width, ice_colors, end) = match sauce(&bytes) {
+            Some((sauce, start)) => (if sauce.width > 0 { sauce.width as usize } else { DEFAULT_WIDTH }, sauce.ice_colors, start),
+            None => (DEFAULT_WIDTH, false, bytes.len())
+        };
+        let data = &bytes[..end];
+
+        let mut screen = Screen { width, cells: Vec::new() };
+        let mut pen = Pen::DEFAULT;
+        let (mut x, mut y) = (0usize, 0usize);
+        let mut saved = (0, 0);
+        let mut i = 0;
+        while i < data.len() {
+            let b = data[i];
+            i += 1;
+            match b {
+                EOF => break,
+                b'\r' => x = 0,
+                b'\n' => {
+                    x = 0;
+                    y += 1;
+                },
+                b'\t' => x = ((x / 8 + 1) * 8).min(width - 1),
+                0x1b if data.get(i) == Some(&b'[') => {
+                    // parameter bytes, then intermediate bytes, then the final byte
+                    let start = i + 1;
+                    let mut end = start;
+                    while end < data.len() && !(0x40..=0x7e).contains(&data[end]) {
+                        end += 1;
+                    }
+                    if end == data.len() {
+                        break;
+                    }
+                    i = end + 1;
+                    let body = &data[start..end];
+                    // private sequences, like ESC[?7h for line wrapping, are not drawing
+                    if body.first().is_some_and(|&b| (b'<'..=b'?').contains(&b)) {
+                        continue;
+                    }
+                    let params: Vec<Option<u32>> = body.split(|&b| b == b';').map(|param| {
+                        std::str::from_utf8(param).ok().and_then(|param| param.parse::<u32>().ok())
+                    }).collect();
+                    let count = |i: usize| params.get(i).copied().flatten().unwrap_or(1).max(1) as usize;
+                    // a full line waiting to wrap counts as being on its last column
+                    let column = x.min(width - 1);
+                    match data[end] {
+                        b'm' => pen.sgr(&params),
+                        b'A' => y = y.saturating_sub(count(0)),
+                        b'B' => y = y.saturating_add(count(0)),
+                        b'C' => x = column.saturating_add(count(0)).min(width - 1),
+                        b'D' => x = column.saturating_sub(count(0)),
+                        b'H' | b'f' => {
+                            y = count(0) - 1;
+                            x = (count(1) - 1).min(width - 1);
+                        },
+                        b'J' if params.first().copied().flatten() == Some(2) => {
+                            screen.cells.clear();
+                            x = 0;
+                            y = 0;
+                        },
+                        b'J' => {
+                            for row in y..screen.rows() {
+                                screen.erase(row, if row == y { column } else { 0 }, width);
+                            }
+                        },
+                        b'K' => match params.first().copied().flatten().unwrap_or(0) {
+                            0 => screen.erase(y, column, width),
+                            1 => screen.erase(y, 0, column + 1),
+                            _ => screen.erase(y, 0, width)
+                        },
+                        b's' => saved = (x, y),
+                        b'u' => {
+                            x = saved.0;
+                            y = saved.1;
+                        },
+                        _ => {}
+                    }
+                },
+                _ => {
+                    // the wrap only happens once there is something to put on the next line, so
+                    // that a line break right after the last column doesn't leave an empty row
+                    if x >= width {
+                        x = 0;
+                        y += 1;
+                    }
+                    screen.row(y)?[x] = (cp437_to_char(b), pen.color(ice_colors));
+                    x += 1;
+                }
+            }
+        }
+        let height = screen.rows();
+        let (glyphs, colors) = screen.cells.into_iter().unzip();
+        Ok(RustConsoleSprite::from_cells(width, height, glyphs, colors))
+    }
+}
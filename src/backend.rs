@@ -0,0 +1,35 @@
+use super::HeadlessBackend;
+#[cfg(target_os = "windows")]
+use super::WinConsoleBackend;
+#[cfg(not(target_os = "windows"))]
+use super::TerminalBackend;
+
+use std::env;
+use std::io::Error;
+
+pub trait ConsoleBackend {
+    fn open(&mut self, width: usize, height: usize, font_width: i16, font_height: i16) -> Result<(), Error>;
+    fn resize(&mut self, width: usize, height: usize, font_width: i16, font_height: i16) -> Result<(), Error>;
+    fn present(&mut self, width: usize, height: usize, glyphs: &[char], colors: &[u16]) -> Result<(), Error>;
+    fn poll_keys(&mut self, key_states: &mut [bool; 256]) -> Result<(), Error>;
+    fn flush_input(&mut self) -> Result<(), Error>;
+    fn set_title(&mut self, title: &str) -> Result<(), Error>;
+}
+
+// RUST_CONSOLE_BACKEND=headless runs a game without any console, e.g. on a CI machine
+pub fn default_backend() -> Box<dyn ConsoleBackend> {
+    match env::var("RUST_CONSOLE_BACKEND").as_deref() {
+        Ok("headless") => Box::new(HeadlessBackend::new()),
+        _ => platform_backend()
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn platform_backend() -> Box<dyn ConsoleBackend> {
+    Box::new(WinConsoleBackend::new())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn platform_backend() -> Box<dyn ConsoleBackend> {
+    Box::new(TerminalBackend::new())
+}
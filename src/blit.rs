@@ -0,0 +1,49 @@
+use super::RustConsoleSprite;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Transparency {
+    None,
+    Glyph(char),
+    Color(u16),
+    // one entry per cell of the whole sprite, row by row, true where the cell is drawn
+    Mask(Vec<bool>)
+}
+
+impl Transparency {
+    pub fn is_transparent(&self, sprite: &RustConsoleSprite, x: usize, y: usize) -> bool {
+        match self {
+            Transparency::None => false,
+            Transparency::Glyph(c) => sprite.get_glyph(x, y) == *c,
+            Transparency::Color(col) => sprite.get_color(x, y) == *col,
+            Transparency::Mask(mask) => !mask.get(y * sprite.width() + x).copied().unwrap_or(false)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlitOptions {
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub flip_horizontal: bool,
+    pub flip_vertical: bool,
+    pub transparency: Transparency
+}
+
+impl BlitOptions {
+    pub fn scaled(scale: f32) -> BlitOptions {
+        BlitOptions { scale_x: scale, scale_y: scale, ..BlitOptions::default() }
+    }
+}
+
+// same as draw_sprite, 1:1 with ' ' transparent
+impl Default for BlitOptions {
+    fn default() -> BlitOptions {
+        BlitOptions {
+            scale_x: 1f32,
+            scale_y: 1f32,
+            flip_horizontal: false,
+            flip_vertical: false,
+            transparency: Transparency::Glyph(' ')
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use super::{FrameStats, KeyState};
+use super::backend::{default_backend, ConsoleBackend};
+use super::draw::DrawTarget;
+use super::rect::Rect;
+use super::sprite::RustConsoleSprite;
+
+use std::io::Error;
+
+pub struct RustConsole {
+    width: usize,
+    height: usize,
+    font_width: i16,
+    font_height: i16,
+    glyphs: Vec<char>,
+    colors: Vec<u16>,
+    keys: [KeyState; 256],
+    old_key_states: [bool; 256],
+    new_key_states: [bool; 256],
+    quit: bool,
+    frame_stats: FrameStats,
+    clips: Vec<Rect>,
+    viewports: Vec<(i32, i32)>,
+    backend: Box<dyn ConsoleBackend>
+}
+
+impl RustConsole {
+    pub const FG_BLACK: u16 = 0x0000;
+    pub const FG_DARK_BLUE: u16 = 0x0001; 
+    pub const FG_DARK_GREEN: u16 = 0x0002;
+    pub const FG_DARK_CYAN: u16 = 0x0003;
+    pub const FG_DARK_RED: u16 = 0x0004;
+    pub const FG_DARK_MAGENTA: u16 = 0x0005;
+    pub const FG_DARK_YELLOW: u16 = 0x0006;
+    pub const FG_GREY: u16 = 0x0007;
+    pub const FG_DARK_GREY: u16 = 0x0008;
+    pub const FG_BLUE: u16 = 0x0009;
+    pub const FG_GREEN: u16 = 0x000a;
+    pub const FG_CYAN: u16 = 0x000b;
+    pub const FG_RED: u16 = 0x000c;
+    pub const FG_MAGENTA: u16 = 0x000d;
+    pub const FG_YELLOW: u16 = 0x000e;
+    pub const FG_WHITE: u16 = 0x000f;
+    pub const BG_BLACK: u16 = 0x0000;
+    pub const BG_DARK_BLUE: u16 = 0x0010;
+    pub const BG_DARK_GREEN: u16 = 0x0020;
+    pub const BG_DARK_CYAN: u16 = 0x0030;
+    pub const BG_DARK_RED: u16 = 0x0040;
+    pub const BG_DARK_MAGENTA: u16 = 0x0050;
+    pub const BG_DARK_YELLOW: u16 = 0x0060;
+    pub const BG_GREY: u16 = 0x0070;
+    pub const BG_DARK_GREY: u16 = 0x0080;
+    pub const BG_BLUE: u16 = 0x0090;
+    pub const BG_GREEN: u16 = 0x00a0;
+    pub const BG_CYAN: u16 = 0x00b0;
+    pub const BG_RED: u16 = 0x00c0;
+    pub const BG_MAGENTA: u16 = 0x00d0;
+    pub const BG_YELLOW: u16 = 0x00e0;
+    pub const BG_WHITE: u16 = 0x00f0;
+    
+    pub const PIXEL_SOLID: char = '\u{2588}';
+    pub const PIXEL_THREEQUARTER: char = '\u{2593}';
+    pub const PIXEL_HALF: char  = '\u{2592}';
+    pub const PIXEL_QUARTER: char = '\u{2591}';
+
+    pub const VK_UP: u32 = 38u32;
+    pub const VK_DOWN: u32 = 40u32;
+    pub const VK_LEFT: u32 = 37u32;
+    pub const VK_RIGHT: u32 = 39u32;
+    pub const VK_ESCAPE: u32 = 27u32;
+    
+    pub(crate) fn new(width: usize, height: usize, font_width: i16, font_height: i16) -> Result<RustConsole, Error> {
+        RustConsole::with_backend(default_backend(), width, height, font_width, font_height)
+    }
+    
+    pub(crate) fn with_backend(mut backend: Box<dyn ConsoleBackend>, width: usize, height: usize, font_width: i16, font_height: i16) -> Result<RustConsole, Error> {
+        backend.open(width, height, font_width, font_height)?;
+        
+        Ok(RustConsole {
+            width,
+            height,
+            font_width,
+            font_height,
+            glyphs: vec![' '; width * height],
+            colors: vec![0; width * height],
+            keys: [KeyState { pressed: false, released: false, held: false }; 256],
+            old_key_states: [false; 256],
+            new_key_states: [false; 256],
+            quit: false,
+            frame_stats: FrameStats::default(),
+            clips: Vec::new(),
+            viewports: Vec::new(),
+            backend
+        })
+    }
+    
+    pub(crate) fn write_output(&mut self) -> Result<(), Error> {
+        self.backend.present(self.width, self.height, &self.glyphs, &self.colors)
+    }
+    
+    pub(crate) fn update_key_states(&mut self) -> Result<(), Error> {
+        self.backend.poll_keys(&mut self.new_key_states)?;
+        
+        // pressed / released stay set until an update has seen them, a frame
+        // can run no update at all with a fixed timestep
+        for v_key in 0..256 {
+            if self.new_key_states[v_key] != self.old_key_states[v_key] {
+                if self.new_key_states[v_key] {
+                    self.keys[v_key].pressed = !self.keys[v_key].held;
+                    self.keys[v_key].held = true;
+                } else {
+                    self.keys[v_key].released = true;
+                    self.keys[v_key].held = false;
+                }
+            }
+            
+            self.old_key_states[v_key] = self.new_key_states[v_key];
+        }
+        Ok(())
+    }
+    
+    pub(crate) fn clear_key_transitions(&mut self) {
+        for key in self.keys.iter_mut() {
+            key.pressed = false;
+            key.released = false;
+        }
+    }
+    
+    pub(crate) fn flush_input_events(&mut self) -> Result<(), Error> {
+        self.backend.flush_input()
+    }
+    
+    pub(crate) fn quit_requested(&self) -> bool { self.quit }
+    
+    pub(crate) fn set_frame_stats(&mut self, frame_stats: FrameStats) { self.frame_stats = frame_stats; }
+    
+    pub fn width(&self) -> usize { self.width }
+    
+    pub fn height(&self) -> usize { self.height }
+    
+    pub fn font_width(&self) -> i16 { self.font_width }
+    
+    pub fn font_height(&self) -> i16 { self.font_height }
+    
+    pub fn key(&self, v_key: usize) -> KeyState { self.keys[v_key] }
+    
+    // the engine stops after the current frame
+    pub fn quit(&mut self) { self.quit = true; }
+    
+    pub fn frame_stats(&self) -> FrameStats { self.frame_stats }
+
+    pub fn set_title(&mut self, title: String) -> Result<(), Error> {
+        self.backend.set_title(&title)
+    }
+    
+    pub fn resize(&mut self, new_width: usize, new_height: usize, new_font_width: i16, new_font_height: i16) -> Result<(), Error> {
+        self.backend.resize(new_width, new_height, new_font_width, new_font_height)?;
+        
+        self.flush_input_events()?;
+        
+        self.width = new_width;
+        self.height = new_height;
+        self.font_width = new_font_width;
+        self.font_height = new_font_height;
+        self.glyphs = vec![' '; new_width * new_height];
+        self.colors = vec![0; new_width * new_height];
+        Ok(())
+    }
+    
+    pub fn get_glyph(&self, x: usize, y: usize) -> char {
+        if x < self.width && y < self.height {
+            self.glyphs[y * self.width + x]
+        } else {
+            ' '
+        }
+    }
+    
+    pub fn get_color(&self, x: usize, y: usize) -> u16 {
+        if x < self.width && y < self.height {
+            self.colors[y * self.width + x]
+        } else {
+            RustConsole::FG_BLACK
+        }
+    }
+    
+    pub fn to_sprite(&self) -> RustConsoleSprite {
+        RustConsoleSprite::from_cells(self.width, self.height, self.glyphs.clone(), self.colors.clone())
+    }
+    
+    // drawing outside rect is clipped, until the matching pop_clip, rect is relative to the current viewport
+    pub fn push_clip(&mut self, rect: Rect) {
+        let (ox, oy) = self.origin();
+        let rect = Rect::new(rect.x.saturating_add(ox), rect.y.saturating_add(oy), rect.width, rect.height);
+        let clip = match self.clips.last() {
+            Some(clip) => clip.intersect(&rect),
+            None => rect
+        };
+        self.clips.push(clip);
+    }
+    
+    pub fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+    
+    // moves the origin of all drawing to (x, y) of the current viewport, until the matching pop_viewport
+    pub fn push_viewport(&mut self, x: i32, y: i32) {
+        let (ox, oy) = self.origin();
+        self.viewports.push((ox.saturating_add(x), oy.saturating_add(y)));
+    }
+    
+    pub fn pop_viewport(&mut self) {
+        self.viewports.pop();
+    }
+}
+
+impl DrawTarget for RustConsole {
+    fn width(&self) -> usize { self.width }
+    
+    fn height(&self) -> usize { self.height }
+    
+    fn set_cell(&mut self, x: usize, y: usize, c: char, col: u16) {
+        if x < self.width && y < self.height {
+            self.glyphs[y * self.width + x] = c;
+            self.colors[y * self.width + x] = col;
+        }
+    }
+    
+    fn origin(&self) -> (i32, i32) {
+        self.viewports.last().copied().unwrap_or((0, 0))
+    }
+    
+    fn clip_rect(&self) -> Rect {
+        let screen = Rect::new(0, 0, self.width as i32, self.height as i32);
+        match self.clips.last() {
+            Some(clip) => clip.intersect(&screen),
+            None => screen
+        }
+    }
+    
+    fn clear(&mut self) {
+        self.glyphs.iter_mut().for_each(|c| *c = ' ');
+        self.colors.iter_mut().for_each(|col| *col = 0);
+    }
+}
@@ -0,0 +1,719 @@
+use super::blit::BlitOptions;
+use super::rect::Rect;
+use super::sprite::RustConsoleSprite;
+use super::text::{layout_text, TextAlign};
+use super::transform::Transform2D;
+
+use std::mem::swap;
+use std::ops::RangeInclusive;
+
+// anything the drawing primitives can render into, implementors only provide the cells,
+// coordinates are relative to origin() and everything outside clip_rect() is dropped
+pub trait DrawTarget {
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    fn set_cell(&mut self, x: usize, y: usize, c: char, col: u16);
+    
+    fn origin(&self) -> (i32, i32) { (0, 0) }
+    
+    // in target coordinates, never larger than the target
+    fn clip_rect(&self) -> Rect {
+        Rect::new(0, 0, self.width() as i32, self.height() as i32)
+    }
+    
+    fn clear(&mut self) {
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                self.set_cell(x, y, ' ', 0);
+            }
+        }
+    }
+    
+    fn draw(&mut self, x: i32, y: i32, c: char, col: u16) {
+        let (x, y) = to_screen(self, x, y);
+        plot(self, x, y, c, col);
+    }
+    
+    fn fill(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, c: char, col: u16) {
+        let (x1, y1) = to_screen(self, x1, y1);
+        let (x2, y2) = to_screen(self, x2, y2);
+        let clip = self.clip_rect();
+        let x1 = x1.clamp(clip.x, clip.right());
+        let x2 = x2.clamp(clip.x, clip.right());
+        let y1 = y1.clamp(clip.y, clip.bottom());
+        let y2 = y2.clamp(clip.y, clip.bottom());
+        for y in y1..y2 {
+            for x in x1..x2 {
+                self.set_cell(x as usize, y as usize, c, col);
+            }
+        }
+    }
+    
+    fn draw_string(&mut self, x: i32, y: i32, s: &str, col: u16) {
+        draw_chars(self, x, y, s, col, false);
+    }
+    
+    fn draw_string_alpha(&mut self, x: i32, y: i32, s: &str, col: u16) {
+        draw_chars(self, x, y, s, col, true);
+    }
+    
+    // lays the text out inside rect, anything that does not fit is cut off
+    fn draw_text(&mut self, rect: Rect, text: &str, col: u16, align: TextAlign, wrap: bool) {
+        if rect.is_empty() {
+            return;
+        }
+        let wrap_width = if wrap { Some(rect.width as usize) } else { None };
+        for (row, line) in layout_text(text, wrap_width).iter().take(rect.height as usize).enumerate() {
+            let length = line.chars().count() as i32;
+            let x = match align {
+                TextAlign::Left => rect.x,
+                TextAlign::Center => rect.x.saturating_add((rect.width - length) / 2),
+                TextAlign::Right => rect.right().saturating_sub(length)
+            };
+            // past i32::MAX the cells pile up on the last column or row, which a rect never contains
+            let y = rect.y.saturating_add(row as i32);
+            for (i, c) in line.chars().enumerate() {
+                let cx = x.saturating_add(i as i32);
+                if rect.contains(cx, y) {
+                    self.draw(cx, y, c, col);
+                }
+            }
+        }
+    }
+    
+    fn draw_line(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, c: char, col: u16) {
+        let (x1, y1) = to_screen(self, x1, y1);
+        let (x2, y2) = to_screen(self, x2, y2);
+        let (x1, y1, x2, y2) = match clip_line(x1, y1, x2, y2, self.clip_rect()) {
+            Some(line) => line,
+            None => return
+        };
+        let dx = x2 - x1;
+        let dy = y2 - y1;
+        let dx1 = dx.abs();
+        let dy1 = dy.abs();
+        let mut px = 2 * dy1 - dx1;
+        let mut py = 2 * dx1 - dy1;
+        if dy1 <= dx1 {
+            let (mut x, mut y, xe) = if dx >= 0 {
+                (x1, y1, x2)
+            } else {
+                (x2, y2, x1)
+            };
+            
+            plot(self, x, y, c, col);
+            
+            while x < xe {
+                x += 1;
+                if px < 0 {
+                    px += 2 * dy1;
+                } else {
+                    if (dx < 0 && dy < 0) || (dx > 0 && dy > 0) {
+                        y += 1;
+                    } else {
+                        y -= 1;
+                    }
+                    px += 2 * (dy1 - dx1);
+                }
+                plot(self, x, y, c, col);
+            }
+        } else {
+            let (mut x, mut y, ye) = if dy >= 0 {
+                (x1, y1, y2)
+            } else {
+                (x2, y2, y1)
+            };
+            
+            plot(self, x, y, c, col);
+            
+            while y < ye {
+                y += 1;
+                if py <= 0 {
+                    py += 2 * dx1;
+                } else {
+                    if (dx < 0 && dy < 0) || (dx > 0 && dy > 0) {
+                        x += 1;
+                    } else {
+                        x -= 1;
+                    }
+                    py += 2 * (dx1 - dy1);
+                }
+                plot(self, x, y, c, col);
+            }
+        }
+    }
+    
+    #[allow(clippy::too_many_arguments)]
+    fn draw_triangle(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, x3: i32, y3: i32, c: char, col: u16) {
+        self.draw_line(x1, y1, x2, y2, c, col);
+        self.draw_line(x2, y2, x3, y3, c, col);
+        self.draw_line(x3, y3, x1, y1, c, col);
+    }
+    
+    #[allow(clippy::too_many_arguments)]
+    fn fill_triangle(&mut self, x1: i32, y1: i32, x2: i32, y2: i32, x3: i32, y3: i32, c: char, col: u16) {
+        let (ox, oy) = self.origin();
+        let (ox, oy) = (ox as i64, oy as i64);
+        let points = [(x1 as i64 + ox, y1 as i64 + oy), (x2 as i64 + ox, y2 as i64 + oy), (x3 as i64 + ox, y3 as i64 + oy)];
+        
+        // nothing to do if the bounding box is entirely clipped
+        let clip = self.clip_rect();
+        let (min_x, max_x) = (points.iter().map(|p| p.0).min().unwrap(), points.iter().map(|p| p.0).max().unwrap());
+        let (min_y, max_y) = (points.iter().map(|p| p.1).min().unwrap(), points.iter().map(|p| p.1).max().unwrap());
+        if max_x < clip.x as i64 || max_y < clip.y as i64 || min_x >= clip.right() as i64 || min_y >= clip.bottom() as i64 {
+            return;
+        }
+        
+        // the scanline fill walks every cell of the edges, so a triangle reaching far outside the clip rect is
+        // first cut down to a band around it, the cuts then fall where nothing is drawn
+        let margin = clip.width.max(clip.height) as i64;
+        let band = (clip.x as i64 - margin, clip.y as i64 - margin, clip.right() as i64 + margin, clip.bottom() as i64 + margin);
+        if min_x >= band.0 && min_y >= band.1 && max_x <= band.2 && max_y <= band.3 {
+            let [p1, p2, p3] = points;
+            scanline_triangle(self, (p1.0 as isize, p1.1 as isize), (p2.0 as isize, p2.1 as isize), (p3.0 as isize, p3.1 as isize), c, col);
+            return;
+        }
+        let polygon = clip_polygon(&points, band);
+        for i in 1..polygon.len().saturating_sub(1) {
+            scanline_triangle(self, polygon[0], polygon[i], polygon[i + 1], c, col);
+        }
+    }
+    
+    fn draw_circle(&mut self, xc: i32, yc: i32, r: i32, c: char, col: u16) {
+        let (xc, yc) = to_screen(self, xc, yc);
+        if r <= 0 || !circle_visible(self, xc, yc, r) { return; }
+        let circle = MidpointCircle::new(r as i64);
+        let (xc, yc) = (xc as i64, yc as i64);
+        
+        for y in clip_rows(self, yc, r as i64) {
+            let k = (y - yc).abs();
+            // the steps that are at this height plot runs of cells, step k plots one cell on each side
+            if let Some((first, last)) = circle.steps_at(k) {
+                draw_span(self, (xc - last) as isize, (xc - first) as isize, y as isize, c, col);
+                draw_span(self, (xc + first) as isize, (xc + last) as isize, y as isize, c, col);
+            }
+            if k <= circle.last {
+                let x = circle.y(k);
+                draw_span(self, (xc - x) as isize, (xc - x) as isize, y as isize, c, col);
+                draw_span(self, (xc + x) as isize, (xc + x) as isize, y as isize, c, col);
+            }
+        }
+    }
+
+    fn fill_circle(&mut self, xc: i32, yc: i32, r: i32, c: char, col: u16) {
+        let (xc, yc) = to_screen(self, xc, yc);
+        if r <= 0 || !circle_visible(self, xc, yc, r) { return; }
+        let circle = MidpointCircle::new(r as i64);
+        let (xc, yc) = (xc as i64, yc as i64);
+        
+        for y in clip_rows(self, yc, r as i64) {
+            let k = (y - yc).abs();
+            // the widest of the spans the steps draw on this row
+            let mut half = circle.steps_at(k).map_or(-1, |(_, last)| last);
+            if k <= circle.last {
+                half = half.max(circle.y(k));
+            }
+            if half >= 0 {
+                draw_span(self, (xc - half) as isize, (xc + half) as isize, y as isize, c, col);
+            }
+        }
+    }
+
+    fn draw_sprite(&mut self, x: i32, y: i32, sprite: &RustConsoleSprite) {
+        let source = Rect::new(0, 0, sprite.width() as i32, sprite.height() as i32);
+        self.draw_partial_sprite(x, y, sprite, source, &BlitOptions::default());
+    }
+    
+    // draws the source rect of sprite with its top left corner at (x, y), the output is the source
+    // size times the scale, rounded, and every output cell takes the nearest source cell
+    fn draw_partial_sprite(&mut self, x: i32, y: i32, sprite: &RustConsoleSprite, source: Rect, options: &BlitOptions) {
+        let width = (source.width as f32 * options.scale_x).round() as i64;
+        let height = (source.height as f32 * options.scale_y).round() as i64;
+        if source.is_empty() || width <= 0 || height <= 0 {
+            return;
+        }
+        
+        // only the part of the output inside the clip rect is visited
+        let (sx, sy) = to_screen(self, x, y);
+        let (sx, sy) = (sx as i64, sy as i64);
+        let clip = self.clip_rect();
+        let (i1, i2) = ((clip.x as i64 - sx).max(0), (clip.right() as i64 - sx).min(width));
+        let (j1, j2) = ((clip.y as i64 - sy).max(0), (clip.bottom() as i64 - sy).min(height));
+        
+        for j in j1..j2 {
+            let mut v = j * source.height as i64 / height;
+            if options.flip_vertical {
+                v = source.height as i64 - 1 - v;
+            }
+            let v = source.y as i64 + v;
+            for i in i1..i2 {
+                let mut u = i * source.width as i64 / width;
+                if options.flip_horizontal {
+                    u = source.width as i64 - 1 - u;
+                }
+                let u = source.x as i64 + u;
+                // parts of the source rect outside the sprite are left transparent
+                if u < 0 || v < 0 || u >= sprite.width() as i64 || v >= sprite.height() as i64 {
+                    continue;
+                }
+                let (u, v) = (u as usize, v as usize);
+                if !options.transparency.is_transparent(sprite, u, v) {
+                    self.set_cell((sx + i) as usize, (sy + j) as usize, sprite.get_glyph(u, v), sprite.get_color(u, v));
+                }
+            }
+        }
+    }
+    
+    // every target cell whose centre maps back inside the sprite is filled, sprite cells
+    // are in sprite coordinates, with (0, 0) at its top left corner, and ' ' is transparent
+    fn draw_sprite_transformed(&mut self, sprite: &RustConsoleSprite, transform: &Transform2D) {
+        let inverse = match transform.inverse() {
+            Some(inverse) => inverse,
+            None => return
+        };
+        let (w, h) = (sprite.width() as f32, sprite.height() as f32);
+        if w == 0f32 || h == 0f32 {
+            return;
+        }
+        
+        // bounding box of the transformed sprite, in target coordinates
+        let corners = [transform.apply(0f32, 0f32), transform.apply(w, 0f32), transform.apply(0f32, h), transform.apply(w, h)];
+        let (ox, oy) = self.origin();
+        let min_x = (corners.iter().map(|p| p.0).fold(f32::INFINITY, f32::min).floor() as i64).saturating_add(ox as i64);
+        let max_x = (corners.iter().map(|p| p.0).fold(f32::NEG_INFINITY, f32::max).ceil() as i64).saturating_add(ox as i64);
+        let min_y = (corners.iter().map(|p| p.1).fold(f32::INFINITY, f32::min).floor() as i64).saturating_add(oy as i64);
+        let max_y = (corners.iter().map(|p| p.1).fold(f32::NEG_INFINITY, f32::max).ceil() as i64).saturating_add(oy as i64);
+        let clip = self.clip_rect();
+        
+        for y in min_y.max(clip.y as i64)..max_y.min(clip.bottom() as i64) {
+            for x in min_x.max(clip.x as i64)..max_x.min(clip.right() as i64) {
+                let (u, v) = inverse.apply((x - ox as i64) as f32 + 0.5f32, (y - oy as i64) as f32 + 0.5f32);
+                if !(u >= 0f32 && v >= 0f32 && u < w && v < h) {
+                    continue;
+                }
+                // the sampling functions take normalised coordinates, and sample one row up
+                let c = sprite.sample_glyph(u / w, (v + 1f32) / h);
+                if c != ' ' {
+                    self.set_cell(x as usize, y as usize, c, sprite.sample_color(u / w, (v + 1f32) / h));
+                }
+            }
+        }
+    }
+
+}
+
+fn to_screen<T: DrawTarget + ?Sized>(target: &T, x: i32, y: i32) -> (i32, i32) {
+    let (ox, oy) = target.origin();
+    (x.saturating_add(ox), y.saturating_add(oy))
+}
+
+// '\n' goes back to x on the next row, whatever falls outside the console is dropped
+fn draw_chars<T: DrawTarget + ?Sized>(target: &mut T, x: i32, y: i32, s: &str, col: u16, alpha: bool) {
+    let mut cx = x;
+    let mut cy = y;
+    for c in s.chars() {
+        if c == '\n' {
+            cx = x;
+            cy = cy.saturating_add(1);
+            continue;
+        }
+        if !alpha || c != ' ' {
+            target.draw(cx, cy, c, col);
+        }
+        cx = cx.saturating_add(1);
+    }
+}
+
+// single cell in target coordinates, dropped if it is clipped
+fn plot<T: DrawTarget + ?Sized>(target: &mut T, x: i32, y: i32, c: char, col: u16) {
+    if target.clip_rect().contains(x, y) {
+        target.set_cell(x as usize, y as usize, c, col);
+    }
+}
+
+// horizontal run of cells from x1 to x2 inclusive in target coordinates, clipped
+fn draw_span<T: DrawTarget + ?Sized>(target: &mut T, x1: isize, x2: isize, y: isize, c: char, col: u16) {
+    let clip = target.clip_rect();
+    if y < clip.y as isize || y >= clip.bottom() as isize {
+        return;
+    }
+    let x1 = x1.max(clip.x as isize);
+    let x2 = x2.min(clip.right() as isize - 1);
+    for x in x1..=x2 {
+        target.set_cell(x as usize, y as usize, c, col);
+    }
+}
+
+fn circle_visible<T: DrawTarget + ?Sized>(target: &T, xc: i32, yc: i32, r: i32) -> bool {
+    let clip = target.clip_rect();
+    xc.saturating_add(r) >= clip.x && yc.saturating_add(r) >= clip.y && xc.saturating_sub(r) < clip.right() && yc.saturating_sub(r) < clip.bottom()
+}
+
+// the scanline fill of the One Lone Coder engine, in target coordinates
+fn scanline_triangle<T: DrawTarget + ?Sized>(target: &mut T, (mut x1, mut y1): (isize, isize), (mut x2, mut y2): (isize, isize), (mut x3, mut y3): (isize, isize), c: char, col: u16) {
+    let mut changed1 = false;
+    let mut changed2 = false;
+    
+    // sort vertices
+    if y1 > y2 {
+        swap(&mut y1, &mut y2);
+        swap(&mut x1, &mut x2);
+    }
+    if y1 > y3 {
+        swap(&mut y1, &mut y3);
+        swap(&mut x1, &mut x3);
+    }
+    if y2 > y3 {
+        swap(&mut y2, &mut y3);
+        swap(&mut x2, &mut x3);
+    }
+    
+    // starting points
+    let mut t1x = x1;
+    let mut t2x = x1;
+    let mut y = y1;
+    let mut dx1 = x2 - x1;
+    let signx1 = if dx1 < 0 {
+        dx1 = -dx1;
+        -1
+    } else {
+        1
+    };
+    let mut dy1 = y2 - y1;
+    
+    let mut dx2 = x3 - x1;
+    let signx2 = if dx2 < 0 {
+        dx2 = -dx2;
+        -1
+    } else {
+        1
+    };
+    let mut dy2 = y3 - y1;
+    
+    if dy1 > dx1 {
+        swap(&mut dx1, & mut dy1);
+        changed1 = true;
+    }
+    if dy2 > dx2 {
+        swap(&mut dy2, &mut dx2);
+        changed2 = true;
+    }
+    
+    let mut e2 = dx2 >> 1;
+    if y1 != y2 { // not flat top, so do the first half
+        let mut e1 = dx1 >> 1;
+        
+        for mut i in 0..dx1 {
+            let mut t1xp = 0;
+            let mut t2xp = 0;
+            let (mut minx, mut maxx) = if t1x < t2x {
+                (t1x, t2x)
+            } else {
+                (t2x, t1x)
+            };
+            // process first line until y value is about to change
+            'first_line_1: while i < dx1 {
+                i += 1;
+                e1 += dy1;
+                while e1 >= dx1 {
+                    e1 -= dx1;
+                    if changed1 {
+                        t1xp = signx1;
+                    } else {
+                        break 'first_line_1;
+                    }
+                }
+                if changed1 {
+                    break 'first_line_1;
+                } else {
+                    t1x += signx1;
+                }
+            }
+            
+            // process second line until y value is about to change
+            'second_line_1: loop {
+                e2 += dy2;
+                while e2 >= dx2 {
+                    e2 -= dx2;
+                    if changed2 {
+                        t2xp = signx2;
+                    } else {
+                        break 'second_line_1;
+                    }
+                }
+                if changed2 {
+                    break 'second_line_1;
+                } else {
+                    t2x += signx2;
+                }
+            }
+            
+            if minx > t1x {
+                minx = t1x;
+            }
+            if minx > t2x {
+                minx = t2x;
+            }
+            if maxx < t1x {
+                maxx = t1x;
+            }
+            if maxx < t2x {
+                maxx = t2x;
+            }
+            // draw line from min to max points found on the y
+            draw_span(target, minx, maxx, y, c, col);
+            
+            // now increase y
+            if !changed1 {
+                t1x += signx1;
+            }
+            t1x += t1xp;
+            if !changed2 {
+                t2x += signx2;
+            }
+            t2x += t2xp;
+            y += 1;
+            if y == y2 {
+                break;
+            }
+        }
+    }
+    
+    // now, do the second half
+    dx1 = x3 - x2;
+    let signx1 = if dx1 < 0 {
+        dx1 = -dx1;
+        -1
+    } else {
+        1
+    };
+    dy1 = y3 - y2;
+    t1x = x2;
+    
+    if dy1 > dx1 {
+        swap(&mut dy1, &mut dx1);
+        changed1 = true;
+    } else {
+        changed1 = false;
+    }
+    let mut e1 = dx1 >> 1;
+    
+    for mut i in 0..=dx1 {
+        let mut t1xp = 0;
+        let mut t2xp = 0;
+        let (mut minx, mut maxx) = if t1x < t2x {
+            (t1x, t2x)
+        } else {
+            (t2x, t1x)
+        };
+        // process first line until y value is about to change
+        'first_line_2: while i < dx1 {
+            e1 += dy1;
+            if e1 >= dx1 {
+                e1 -= dx1;
+                if changed1 {
+                    t1xp = signx1;
+                } else {
+                    break 'first_line_2;
+                }
+            }
+            if changed1 {
+                break 'first_line_2;
+            } else {
+                t1x += signx1;
+            }
+            if i < dx1 {
+                i += 1;
+            }
+        }
+        
+        // process second line until y value is about to change
+        'second_line_2: while t2x != x3 {
+            e2 += dy2;
+            while e2 >= dx2 {
+                e2 -= dx2;
+                if changed2 {
+                    t2xp = signx2;
+                } else {
+                    break 'second_line_2;
+                }
+            }
+            if changed2 {
+                break 'second_line_2;
+            } else {
+                t2x += signx2;
+            }
+        }
+        
+        if minx > t1x {
+            minx = t1x;
+        }
+        if minx > t2x {
+            minx = t2x;
+        }
+        if maxx < t1x {
+            maxx = t1x;
+        }
+        if maxx < t2x {
+            maxx = t2x;
+        }
+        // draw line from min to max points found on the y
+        draw_span(target, minx, maxx, y, c, col);
+        
+        // now increase y
+        if !changed1 {
+            t1x += signx1;
+        }
+        t1x += t1xp;
+        if !changed2 {
+            t2x += signx2;
+        }
+        t2x += t2xp;
+        y += 1;
+        if y > y3 {
+            return;
+        }
+    }
+}
+
+// the triangle cut to the rectangle (left, top, right, bottom) one side at a time, with its corners
+// rounded back to cells, as a convex polygon
+fn clip_polygon(points: &[(i64, i64)], (left, top, right, bottom): (i64, i64, i64, i64)) -> Vec<(isize, isize)> {
+    let mut polygon: Vec<(f64, f64)> = points.iter().map(|&(x, y)| (x as f64, y as f64)).collect();
+    // the coordinate each side bounds, and its limit, negated for the lower bounds so that inside is always <=
+    let sides = [(0, -left as f64, -1f64), (0, right as f64, 1f64), (1, -top as f64, -1f64), (1, bottom as f64, 1f64)];
+    for &(axis, limit, sign) in &sides {
+        let value = |p: (f64, f64)| sign * if axis == 0 { p.0 } else { p.1 };
+        let mut clipped = Vec::new();
+        for i in 0..polygon.len() {
+            let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+            if value(a) <= limit {
+                clipped.push(a);
+            }
+            if (value(a) <= limit) != (value(b) <= limit) {
+                let t = (limit - value(a)) / (value(b) - value(a));
+                clipped.push((a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t));
+            }
+        }
+        polygon = clipped;
+    }
+    polygon.iter().map(|&(x, y)| (x.round() as isize, y.round() as isize)).collect()
+}
+
+// the rows from yc - r to yc + r that are inside the clip rect
+fn clip_rows<T: DrawTarget + ?Sized>(target: &T, yc: i64, r: i64) -> RangeInclusive<i64> {
+    let clip = target.clip_rect();
+    (yc - r).max(clip.y as i64)..=(yc + r).min(clip.bottom() as i64 - 1)
+}
+
+// the midpoint circle worked out a row at a time: its decision variable is 2(x + 1)² + y² + (y - 1)² - 2r²,
+// so step x is at the largest y with y² + (y - 1)² < 2(r² - x²), and the steps go on while y >= x
+struct MidpointCircle {
+    r: i128,
+    last: i64
+}
+
+impl MidpointCircle {
+    fn new(r: i64) -> MidpointCircle {
+        let mut circle = MidpointCircle { r: r as i128, last: 0 };
+        let (mut lo, mut hi) = (0, r);
+        while lo < hi {
+            let mid = lo + (hi - lo + 1) / 2;
+            if circle.y(mid) >= mid {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        circle.last = lo;
+        circle
+    }
+
+    // the height of step x
+    fn y(&self, x: i64) -> i64 {
+        let t = 2 * (self.r * self.r - (x as i128).pow(2));
+        if t < 1 {
+            return 0;
+        }
+        // (2y - 1)² <= 2t - 2
+        (((2 * t - 2) as u128).isqrt() as i64 + 1) / 2
+    }
+
+    // how many steps are at height k or above
+    fn steps_from(&self, k: i64) -> i64 {
+        let k = k as i128;
+        let m = 2 * self.r * self.r - (k * k + (k - 1) * (k - 1));
+        if m <= 0 { 0 } else { (((m - 1) / 2) as u128).isqrt() as i64 + 1 }
+    }
+
+    // the first and last steps at height k
+    fn steps_at(&self, k: i64) -> Option<(i64, i64)> {
+        let (first, last) = (self.steps_from(k + 1), (self.steps_from(k) - 1).min(self.last));
+        if first <= last { Some((first, last)) } else { None }
+    }
+}
+
+// cohen-sutherland region codes
+const INSIDE: u8 = 0;
+const LEFT: u8 = 1;
+const RIGHT: u8 = 2;
+const TOP: u8 = 4;
+const BOTTOM: u8 = 8;
+
+fn outcode(x: f64, y: f64, xmin: f64, ymin: f64, xmax: f64, ymax: f64) -> u8 {
+    let mut code = INSIDE;
+    if x < xmin {
+        code |= LEFT;
+    } else if x > xmax {
+        code |= RIGHT;
+    }
+    if y < ymin {
+        code |= TOP;
+    } else if y > ymax {
+        code |= BOTTOM;
+    }
+    code
+}
+
+// clips a line to the cells inside clip, returns None if nothing is left
+fn clip_line(x1: i32, y1: i32, x2: i32, y2: i32, clip: Rect) -> Option<(i32, i32, i32, i32)> {
+    if clip.is_empty() {
+        return None;
+    }
+    let (xmin, ymin) = (clip.x as f64, clip.y as f64);
+    let (xmax, ymax) = (clip.right() as f64 - 1.0, clip.bottom() as f64 - 1.0);
+    let (mut x1, mut y1, mut x2, mut y2) = (x1 as f64, y1 as f64, x2 as f64, y2 as f64);
+    let mut code1 = outcode(x1, y1, xmin, ymin, xmax, ymax);
+    let mut code2 = outcode(x2, y2, xmin, ymin, xmax, ymax);
+    
+    loop {
+        if code1 | code2 == INSIDE {
+            return Some((x1.round() as i32, y1.round() as i32, x2.round() as i32, y2.round() as i32));
+        }
+        if code1 & code2 != INSIDE {
+            return None;
+        }
+        
+        // move the outside point onto the edge it is beyond
+        let code = if code1 != INSIDE { code1 } else { code2 };
+        let (x, y) = if code & TOP != 0 {
+            (x1 + (x2 - x1) * (ymin - y1) / (y2 - y1), ymin)
+        } else if code & BOTTOM != 0 {
+            (x1 + (x2 - x1) * (ymax - y1) / (y2 - y1), ymax)
+        } else if code & RIGHT != 0 {
+            (xmax, y1 + (y2 - y1) * (xmax - x1) / (x2 - x1))
+        } else {
+            (xmin, y1 + (y2 - y1) * (xmin - x1) / (x2 - x1))
+        };
+        
+        if code == code1 {
+            x1 = x;
+            y1 = y;
+            code1 = outcode(x1, y1, xmin, ymin, xmax, ymax);
+        } else {
+            x2 = x;
+            y2 = y;
+            code2 = outcode(x2, y2, xmin, ymin, xmax, ymax);
+        }
+    }
+}
@@ -1,43 +1,227 @@
-use super::{RustConsole, RustConsoleGame};
+use super::{ConsoleBackend, DrawTarget, EngineError, FrameStats, RustConsole, RustConsoleGame};
+use super::stats::FrameTimes;
 
-use std::time::Instant;
-use std::io::Error;
+use std::hint;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// sleeping is not precise enough, the end of each frame is spent spinning
+const SPIN_TIME: Duration = Duration::from_millis(2);
+
+// the fps in the title and in the overlay is refreshed this often, in seconds
+const TITLE_REFRESH: f32 = 0.5f32;
+
+pub type TitleFormat = Box<dyn FnMut(&str, &FrameStats) -> String>;
+
+pub enum TitlePolicy {
+    // "RustConsoleGameEngine - <name> - FPS: <fps>"
+    Fps,
+    // set once, the game can change it later with set_title
+    Static(String),
+    // gets the game name and the frame stats
+    Format(TitleFormat),
+    // the title is the game name, the fps are drawn in the top right corner of the frame
+    Overlay
+}
+
+struct FixedTimestep {
+    tick: f32,
+    max_updates: u32,
+    accumulator: f32
+}
 
 pub struct RustConsoleGameEngine<'a> {
     console: RustConsole,
-    game: &'a mut dyn RustConsoleGame
+    game: &'a mut dyn RustConsoleGame,
+    fixed_timestep: Option<FixedTimestep>,
+    target_frame_time: Option<Duration>,
+    frame_times: FrameTimes,
+    title_policy: TitlePolicy,
+    title_set: bool,
+    title_elapsed: f32,
+    title: String,
+    overlay: String
 }
 
 impl<'a> RustConsoleGameEngine<'a> {
-    pub fn new(game: &'a mut dyn RustConsoleGame, width: usize, height: usize, font_width: i16, font_height: i16) -> Result<RustConsoleGameEngine, Error> {
+    pub fn new(game: &'a mut dyn RustConsoleGame, width: usize, height: usize, font_width: i16, font_height: i16) -> Result<RustConsoleGameEngine<'a>, EngineError> {
         Ok(RustConsoleGameEngine {
             console: RustConsole::new(width, height, font_width, font_height)?,
-            game
+            game,
+            fixed_timestep: None,
+            target_frame_time: None,
+            frame_times: FrameTimes::new(),
+            title_policy: TitlePolicy::Fps,
+            title_set: false,
+            title_elapsed: 0f32,
+            title: String::new(),
+            overlay: String::new()
         })
     }
     
-    pub fn run(&mut self) {
-        self.game.setup();
+    pub fn with_backend(game: &'a mut dyn RustConsoleGame, backend: Box<dyn ConsoleBackend>, width: usize, height: usize, font_width: i16, font_height: i16) -> Result<RustConsoleGameEngine<'a>, EngineError> {
+        Ok(RustConsoleGameEngine {
+            console: RustConsole::with_backend(backend, width, height, font_width, font_height)?,
+            game,
+            fixed_timestep: None,
+            target_frame_time: None,
+            frame_times: FrameTimes::new(),
+            title_policy: TitlePolicy::Fps,
+            title_set: false,
+            title_elapsed: 0f32,
+            title: String::new(),
+            overlay: String::new()
+        })
+    }
+    
+    pub fn console(&self) -> &RustConsole { &self.console }
+    
+    // update runs tick_rate times per second with a constant elapsed time, at most
+    // max_updates times per frame, the time that can't be caught up with is dropped
+    pub fn set_fixed_timestep(&mut self, tick_rate: f32, max_updates: u32) -> Result<(), EngineError> {
+        if !tick_rate.is_finite() || tick_rate <= 0f32 || max_updates == 0 {
+            return Err(EngineError::InvalidTimestep { tick_rate, max_updates });
+        }
+        self.fixed_timestep = Some(FixedTimestep {
+            tick: 1f32 / tick_rate,
+            max_updates,
+            accumulator: 0f32
+        });
+        Ok(())
+    }
+    
+    pub fn set_variable_timestep(&mut self) {
+        self.fixed_timestep = None;
+    }
+    
+    // a rate that isn't finite and above zero, or too small to make a frame time, means unlimited,
+    // as it may come straight from a settings file
+    pub fn set_target_fps(&mut self, fps: f32) {
+        self.target_frame_time = if fps.is_finite() && fps > 0f32 { Duration::try_from_secs_f32(1f32 / fps).ok() } else { None };
+    }
+    
+    pub fn set_unlimited_fps(&mut self) {
+        self.target_frame_time = None;
+    }
+    
+    pub fn set_title_policy(&mut self, title_policy: TitlePolicy) {
+        self.title_policy = title_policy;
+        self.title_set = false;
+    }
+    
+    pub fn run(&mut self) -> Result<(), EngineError> {
+        let result = self.run_loop();
+        
+        let destroyed = self.game.on_destroy().map_err(EngineError::Game);
         
-        self.console.flush_input_events();
+        result.and(destroyed)
+    }
+    
+    fn run_loop(&mut self) -> Result<(), EngineError> {
+        self.start()?;
         
         let mut tp1 = Instant::now();
         let mut tp2;
         
-        loop {
+        while !self.console.quit_requested() {
             tp2 = Instant::now();
             let elapsed_time = tp2.duration_since(tp1).as_secs_f32();
             tp1 = tp2;
             
-            self.console.update_key_states();
-            
-            self.console.handle_input_events();
-            
-            self.game.update(&mut self.console, elapsed_time);
-            
-            self.console.set_title(format!("RustConsoleGameEngine - {} - FPS: {:3.2}", self.game.name(), 1f32 / elapsed_time));
+            self.step(elapsed_time)?;
             
-            self.console.write_output();
+            if let Some(target_frame_time) = self.target_frame_time {
+                wait_until(tp1 + target_frame_time);
+            }
         }
+        Ok(())
+    }
+    
+    pub fn start(&mut self) -> Result<(), EngineError> {
+        self.game.setup().map_err(EngineError::Game)?;
+        
+        self.console.flush_input_events()?;
+        Ok(())
+    }
+    
+    pub fn step(&mut self, elapsed_time: f32) -> Result<(), EngineError> {
+        let frame_stats = self.frame_times.push(elapsed_time);
+        self.console.set_frame_stats(frame_stats);
+        
+        self.console.update_key_states()?;
+        
+        let alpha = match self.fixed_timestep.as_mut() {
+            Some(timestep) => {
+                timestep.accumulator += elapsed_time;
+                let mut updates = 0;
+                // a game that quits, or a scene stack that has run out of scenes, gets no more ticks
+                while timestep.accumulator >= timestep.tick && updates < timestep.max_updates && !self.console.quit_requested() {
+                    self.game.update(&mut self.console, timestep.tick).map_err(EngineError::Game)?;
+                    self.console.clear_key_transitions();
+                    timestep.accumulator -= timestep.tick;
+                    updates += 1;
+                }
+                if timestep.accumulator >= timestep.tick {
+                    timestep.accumulator %= timestep.tick;
+                }
+                timestep.accumulator / timestep.tick
+            },
+            None => {
+                self.game.update(&mut self.console, elapsed_time).map_err(EngineError::Game)?;
+                self.console.clear_key_transitions();
+                1f32
+            }
+        };
+        
+        self.game.render(&mut self.console, alpha).map_err(EngineError::Game)?;
+        
+        self.update_title(elapsed_time, frame_stats)?;
+        
+        self.console.write_output()?;
+        Ok(())
+    }
+    
+    fn update_title(&mut self, elapsed_time: f32, frame_stats: FrameStats) -> Result<(), EngineError> {
+        self.title_elapsed += elapsed_time;
+        let refresh = !self.title_set || self.title_elapsed >= TITLE_REFRESH;
+        if refresh {
+            self.title_elapsed = 0f32;
+        }
+        
+        let title = match &mut self.title_policy {
+            TitlePolicy::Fps if refresh => Some(format!("RustConsoleGameEngine - {} - FPS: {:3.2}", self.game.name(), frame_stats.fps())),
+            TitlePolicy::Format(format) if refresh => Some(format(self.game.name(), &frame_stats)),
+            TitlePolicy::Static(title) if !self.title_set => Some(title.clone()),
+            TitlePolicy::Overlay => {
+                if refresh {
+                    self.overlay = format!("FPS: {:3.2}", frame_stats.fps());
+                }
+                let length = self.overlay.chars().count() as i32;
+                self.console.draw_string(self.console.width() as i32 - length, 0, &self.overlay, RustConsole::FG_WHITE);
+                if !self.title_set { Some(self.game.name().to_string()) } else { None }
+            },
+            _ => None
+        };
+        
+        if let Some(title) = title {
+            if !self.title_set || title != self.title {
+                self.console.set_title(title.clone())?;
+                self.title = title;
+            }
+            self.title_set = true;
+        }
+        Ok(())
+    }
+    
+    pub fn quit_requested(&self) -> bool { self.console.quit_requested() }
+}
+
+fn wait_until(deadline: Instant) {
+    let now = Instant::now();
+    if deadline > now + SPIN_TIME {
+        thread::sleep(deadline - now - SPIN_TIME);
+    }
+    while Instant::now() < deadline {
+        hint::spin_loop();
     }
 }
\ No newline at end of file
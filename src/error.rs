@@ -0,0 +1,139 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum EngineError {
+    Console(io::Error),
+    Game(Box<dyn Error>),
+    // the tick rate has to be finite and above zero, and at least one update has to run per frame
+    InvalidTimestep { tick_rate: f32, max_updates: u32 }
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EngineError::Console(error) => write!(f, "console error: {}", error),
+            EngineError::Game(error) => write!(f, "game error: {}", error),
+            EngineError::InvalidTimestep { tick_rate, max_updates } => write!(f, "invalid fixed timestep of {} ticks per second with at most {} updates per frame", tick_rate, max_updates)
+        }
+    }
+}
+
+impl Error for EngineError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            EngineError::Console(error) => Some(error),
+            EngineError::Game(error) => Some(error.as_ref()),
+            EngineError::InvalidTimestep { .. } => None
+        }
+    }
+}
+
+impl From<io::Error> for EngineError {
+    fn from(error: io::Error) -> EngineError {
+        EngineError::Console(error)
+    }
+}
+
+#[derive(Debug)]
+pub enum SpriteError {
+    Io(io::Error),
+    // the data ended after found bytes, expected bytes were needed
+    Truncated { expected: usize, found: usize },
+    InvalidGlyph { x: usize, y: usize, code: u32 },
+    TooLarge { width: usize, height: usize },
+    TrailingBytes,
+    // anything else wrong with the contents of the file, in its own format
+    Format(String)
+}
+
+impl fmt::Display for SpriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpriteError::Io(error) => write!(f, "sprite i/o error: {}", error),
+            SpriteError::Truncated { expected, found } => write!(f, "sprite data is truncated, expected {} bytes but found {}", expected, found),
+            SpriteError::InvalidGlyph { x, y, code } => write!(f, "invalid sprite glyph {:#06x} at ({}, {})", code, x, y),
+            SpriteError::TooLarge { width, height } => write!(f, "sprite of {}x{} cells is too large", width, height),
+            SpriteError::TrailingBytes => write!(f, "unexpected bytes after the sprite data"),
+            SpriteError::Format(message) => write!(f, "{}", message)
+        }
+    }
+}
+
+impl Error for SpriteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SpriteError::Io(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for SpriteError {
+    fn from(error: io::Error) -> SpriteError {
+        SpriteError::Io(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnimationError {
+    // a grid needs frames of at least one cell
+    InvalidFrameSize { width: usize, height: usize },
+    EmptyClip(String),
+    FrameOutOfRange { clip: String, frame: usize },
+    // durations are in seconds, finite and above zero
+    InvalidDuration { clip: String, duration: f32 },
+    UnknownClip(String)
+}
+
+impl fmt::Display for AnimationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnimationError::InvalidFrameSize { width, height } => write!(f, "invalid frame size {}x{}", width, height),
+            AnimationError::EmptyClip(clip) => write!(f, "clip '{}' has no frames", clip),
+            AnimationError::FrameOutOfRange { clip, frame } => write!(f, "clip '{}' uses frame {} which does not exist", clip, frame),
+            AnimationError::InvalidDuration { clip, duration } => write!(f, "clip '{}' has an invalid frame duration of {}", clip, duration),
+            AnimationError::UnknownClip(clip) => write!(f, "unknown clip '{}'", clip)
+        }
+    }
+}
+
+impl Error for AnimationError {}
+
+#[derive(Debug)]
+pub enum TiledError {
+    Io(io::Error),
+    // the file is not valid json or xml, or is missing something a map needs
+    Format(String),
+    // infinite maps, orientations other than orthogonal and the like
+    Unsupported(String),
+    // tiles of this tileset have neither a sheet nor a glyph property
+    MissingSheet(String)
+}
+
+impl fmt::Display for TiledError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TiledError::Io(error) => write!(f, "tiled i/o error: {}", error),
+            TiledError::Format(message) => write!(f, "invalid tiled map: {}", message),
+            TiledError::Unsupported(feature) => write!(f, "unsupported tiled feature: {}", feature),
+            TiledError::MissingSheet(tileset) => write!(f, "no sheet for tiled tileset '{}'", tileset)
+        }
+    }
+}
+
+impl Error for TiledError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            TiledError::Io(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for TiledError {
+    fn from(error: io::Error) -> TiledError {
+        TiledError::Io(error)
+    }
+}
@@ -0,0 +1,77 @@
+use super::{RustConsole, RustConsoleSprite, SpriteError};
+use super::font::glyph_mask;
+use super::palette::PALETTE;
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+impl RustConsoleSprite {
+    // a budget of pixels, as many as 8192x8192 in any shape, larger images are rejected before they are allocated
+    pub const MAX_PIXELS: usize = 1 << 26;
+    
+    pub fn save_png(&self, path: &str, font_width: usize, font_height: usize) -> Result<(), SpriteError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_png(&mut writer, font_width, font_height)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    // every cell becomes font_width x font_height pixels of the built-in font, in the colours of PALETTE
+    pub fn write_png(&self, writer: impl Write, font_width: usize, font_height: usize) -> Result<(), SpriteError> {
+        if self.width() == 0 || self.height() == 0 || font_width == 0 || font_height == 0 {
+            return Err(SpriteError::Format(format!("cannot export a {}x{} sprite with a {}x{} font", self.width(), self.height(), font_width, font_height)));
+        }
+        let image_width = self.width().checked_mul(font_width).and_then(|width| u32::try_from(width).ok());
+        let image_height = self.height().checked_mul(font_height).and_then(|height| u32::try_from(height).ok());
+        let (image_width, image_height) = match (image_width, image_height) {
+            (Some(width), Some(height)) if (width as usize).checked_mul(height as usize).is_some_and(|pixels| pixels <= RustConsoleSprite::MAX_PIXELS) => (width, height),
+            _ => return Err(SpriteError::TooLarge { width: self.width(), height: self.height() })
+        };
+
+        let row_length = image_width as usize * 3;
+        let mut pixels = vec![0u8; row_length * image_height as usize];
+        let mut masks = HashMap::new();
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let c = self.get_glyph(x, y);
+                let col = self.get_color(x, y);
+                let fg = PALETTE[(col & 0x000f) as usize];
+                let bg = PALETTE[((col & 0x00f0) >> 4) as usize];
+                let mask = masks.entry(c).or_insert_with(|| glyph_mask(c, font_width, font_height));
+                for py in 0..font_height {
+                    let start = (y * font_height + py) * row_length + x * font_width * 3;
+                    for (px, pixel) in pixels[start..start + font_width * 3].chunks_mut(3).enumerate() {
+                        pixel.copy_from_slice(if mask[py * font_width + px] { &fg } else { &bg });
+                    }
+                }
+            }
+        }
+
+        let mut encoder = png::Encoder::new(writer, image_width, image_height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(png_error)?;
+        writer.write_image_data(&pixels).map_err(png_error)?;
+        writer.finish().map_err(png_error)
+    }
+}
+
+impl RustConsole {
+    // the current frame, at the console's font size
+    pub fn save_png(&self, path: &str) -> Result<(), SpriteError> {
+        self.to_sprite().save_png(path, self.font_width() as usize, self.font_height() as usize)
+    }
+
+    pub fn write_png(&self, writer: impl Write) -> Result<(), SpriteError> {
+        self.to_sprite().write_png(writer, self.font_width() as usize, self.font_height() as usize)
+    }
+}
+
+fn png_error(error: png::EncodingError) -> SpriteError {
+    match error {
+        png::EncodingError::IoError(error) => SpriteError::Io(error),
+        error => SpriteError::Format(format!("cannot encode png: {}", error))
+    }
+}
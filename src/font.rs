@@ -0,0 +1,267 @@
+use std::cmp;
+
+// the 5x8 glyphs of the public domain x11 misc-fixed font for ' ' to '~', one byte per row,
+// the highest of the 5 bits is the leftmost pixel
+const ASCII: [[u8; 8]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04, 0x00], // '!'
+    [0x00, 0x0a, 0x0a, 0x0a, 0x00, 0x00, 0x00, 0x00], // '"'
+    [0x0a, 0x0a, 0x1f, 0x0a, 0x1f, 0x0a, 0x0a, 0x00], // '#'
+    [0x04, 0x0e, 0x14, 0x0e, 0x05, 0x0e, 0x04, 0x00], // '$'
+    [0x00, 0x08, 0x0a, 0x04, 0x0a, 0x02, 0x00, 0x00], // '%'
+    [0x08, 0x14, 0x14, 0x08, 0x14, 0x14, 0x0a, 0x00], // '&'
+    [0x00, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00], // '\''
+    [0x00, 0x04, 0x08, 0x08, 0x08, 0x08, 0x04, 0x00], // '('
+    [0x00, 0x08, 0x04, 0x04, 0x04, 0x04, 0x08, 0x00], // ')'
+    [0x00, 0x00, 0x12, 0x0c, 0x1e, 0x0c, 0x12, 0x00], // '*'
+    [0x00, 0x00, 0x04, 0x04, 0x1f, 0x04, 0x04, 0x00], // '+'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x04, 0x08], // ','
+    [0x00, 0x00, 0x00, 0x00, 0x1e, 0x00, 0x00, 0x00], // '-'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x0e, 0x04], // '.'
+    [0x00, 0x02, 0x02, 0x04, 0x08, 0x10, 0x10, 0x00], // '/'
+    [0x00, 0x04, 0x0a, 0x0a, 0x0a, 0x0a, 0x04, 0x00], // '0'
+    [0x00, 0x04, 0x0c, 0x04, 0x04, 0x04, 0x0e, 0x00], // '1'
+    [0x00, 0x0c, 0x12, 0x02, 0x0c, 0x10, 0x1e, 0x00], // '2'
+    [0x00, 0x1e, 0x04, 0x0c, 0x02, 0x12, 0x0c, 0x00], // '3'
+    [0x00, 0x04, 0x0c, 0x14, 0x1e, 0x04, 0x04, 0x00], // '4'
+    [0x00, 0x1e, 0x10, 0x1c, 0x02, 0x12, 0x0c, 0x00], // '5'
+    [0x00, 0x0c, 0x10, 0x1c, 0x12, 0x12, 0x0c, 0x00], // '6'
+    [0x00, 0x1e, 0x02, 0x04, 0x04, 0x08, 0x08, 0x00], // '7'
+    [0x00, 0x0c, 0x12, 0x0c, 0x12, 0x12, 0x0c, 0x00], // '8'
+    [0x00, 0x0c, 0x12, 0x12, 0x0e, 0x02, 0x0c, 0x00], // '9'
+    [0x00, 0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x0c, 0x00], // ':'
+    [0x00, 0x00, 0x06, 0x06, 0x00, 0x06, 0x04, 0x08], // ';'
+    [0x00, 0x02, 0x04, 0x08, 0x08, 0x04, 0x02, 0x00], // '<'
+    [0x00, 0x00, 0x00, 0x1e, 0x00, 0x1e, 0x00, 0x00], // '='
+    [0x00, 0x08, 0x04, 0x02, 0x02, 0x04, 0x08, 0x00], // '>'
+    [0x00, 0x04, 0x0a, 0x02, 0x04, 0x00, 0x04, 0x00], // '?'
+    [0x06, 0x09, 0x13, 0x15, 0x15, 0x12, 0x08, 0x06], // '@'
+    [0x00, 0x0c, 0x12, 0x12, 0x1e, 0x12, 0x12, 0x00], // 'A'
+    [0x00, 0x1c, 0x12, 0x1c, 0x12, 0x12, 0x1c, 0x00], // 'B'
+    [0x00, 0x0c, 0x12, 0x10, 0x10, 0x12, 0x0c, 0x00], // 'C'
+    [0x00, 0x1c, 0x12, 0x12, 0x12, 0x12, 0x1c, 0x00], // 'D'
+    [0x00, 0x1e, 0x10, 0x1c, 0x10, 0x10, 0x1e, 0x00], // 'E'
+    [0x00, 0x1e, 0x10, 0x1c, 0x10, 0x10, 0x10, 0x00], // 'F'
+    [0x00, 0x0c, 0x12, 0x10, 0x16, 0x12, 0x0c, 0x00], // 'G'
+    [0x00, 0x12, 0x12, 0x1e, 0x12, 0x12, 0x12, 0x00], // 'H'
+    [0x00, 0x0e, 0x04, 0x04, 0x04, 0x04, 0x0e, 0x00], // 'I'
+    [0x00, 0x0e, 0x04, 0x04, 0x04, 0x14, 0x08, 0x00], // 'J'
+    [0x00, 0x12, 0x14, 0x18, 0x14, 0x14, 0x12, 0x00], // 'K'
+    [0x00, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1e, 0x00], // 'L'
+    [0x00, 0x12, 0x1e, 0x1e, 0x12, 0x12, 0x12, 0x00], // 'M'
+    [0x00, 0x12, 0x1a, 0x1e, 0x16, 0x16, 0x12, 0x00], // 'N'
+    [0x00, 0x0c, 0x12, 0x12, 0x12, 0x12, 0x0c, 0x00], // 'O'
+    [0x00, 0x1c, 0x12, 0x12, 0x1c, 0x10, 0x10, 0x00], // 'P'
+    [0x00, 0x0c, 0x12, 0x12, 0x1a, 0x16, 0x0c, 0x02], // 'Q'
+    [0x00, 0x1c, 0x12, 0x12, 0x1c, 0x12, 0x12, 0x00], // 'R'
+    [0x00, 0x0c, 0x12, 0x08, 0x04, 0x12, 0x0c, 0x00], // 'S'
+    [0x00, 0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00], // 'T'
+    [0x00, 0x12, 0x12, 0x12, 0x12, 0x12, 0x0c, 0x00], // 'U'
+    [0x00, 0x12, 0x12, 0x12, 0x12, 0x0c, 0x0c, 0x00], // 'V'
+    [0x00, 0x12, 0x12, 0x12, 0x1e, 0x1e, 0x12, 0x00], // 'W'
+    [0x00, 0x12, 0x12, 0x0c, 0x0c, 0x12, 0x12, 0x00], // 'X'
+    [0x00, 0x11, 0x11, 0x0a, 0x04, 0x04, 0x04, 0x00], // 'Y'
+    [0x00, 0x1e, 0x02, 0x04, 0x08, 0x10, 0x1e, 0x00], // 'Z'
+    [0x00, 0x0e, 0x08, 0x08, 0x08, 0x08, 0x0e, 0x00], // '['
+    [0x00, 0x10, 0x10, 0x08, 0x04, 0x02, 0x02, 0x00], // '\\'
+    [0x00, 0x0e, 0x02, 0x02, 0x02, 0x02, 0x0e, 0x00], // ']'
+    [0x00, 0x04, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00], // '^'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1e], // '_'
+    [0x00, 0x08, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00], // '`'
+    [0x00, 0x00, 0x00, 0x0e, 0x12, 0x12, 0x0e, 0x00], // 'a'
+    [0x00, 0x10, 0x10, 0x1c, 0x12, 0x12, 0x1c, 0x00], // 'b'
+    [0x00, 0x00, 0x00, 0x06, 0x08, 0x08, 0x06, 0x00], // 'c'
+    [0x00, 0x02, 0x02, 0x0e, 0x12, 0x12, 0x0e, 0x00], // 'd'
+    [0x00, 0x00, 0x00, 0x0c, 0x16, 0x18, 0x0c, 0x00], // 'e'
+    [0x00, 0x04, 0x0a, 0x08, 0x1c, 0x08, 0x08, 0x00], // 'f'
+    [0x00, 0x00, 0x00, 0x0c, 0x12, 0x0e, 0x02, 0x0c], // 'g'
+    [0x00, 0x10, 0x10, 0x1c, 0x12, 0x12, 0x12, 0x00], // 'h'
+    [0x00, 0x04, 0x00, 0x0c, 0x04, 0x04, 0x0e, 0x00], // 'i'
+    [0x00, 0x02, 0x00, 0x02, 0x02, 0x02, 0x0a, 0x04], // 'j'
+    [0x00, 0x10, 0x10, 0x12, 0x1c, 0x12, 0x12, 0x00], // 'k'
+    [0x00, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e, 0x00], // 'l'
+    [0x00, 0x00, 0x00, 0x1a, 0x15, 0x15, 0x15, 0x00], // 'm'
+    [0x00, 0x00, 0x00, 0x1c, 0x12, 0x12, 0x12, 0x00], // 'n'
+    [0x00, 0x00, 0x00, 0x0c, 0x12, 0x12, 0x0c, 0x00], // 'o'
+    [0x00, 0x00, 0x00, 0x1c, 0x12, 0x1c, 0x10, 0x10], // 'p'
+    [0x00, 0x00, 0x00, 0x0e, 0x12, 0x0e, 0x02, 0x02], // 'q'
+    [0x00, 0x00, 0x00, 0x14, 0x1a, 0x10, 0x10, 0x00], // 'r'
+    [0x00, 0x00, 0x00, 0x06, 0x0c, 0x02, 0x0c, 0x00], // 's'
+    [0x00, 0x08, 0x08, 0x1c, 0x08, 0x0a, 0x04, 0x00], // 't'
+    [0x00, 0x00, 0x00, 0x12, 0x12, 0x12, 0x0e, 0x00], // 'u'
+    [0x00, 0x00, 0x00, 0x0a, 0x0a, 0x0a, 0x04, 0x00], // 'v'
+    [0x00, 0x00, 0x00, 0x11, 0x15, 0x15, 0x0a, 0x00], // 'w'
+    [0x00, 0x00, 0x00, 0x12, 0x0c, 0x0c, 0x12, 0x00], // 'x'
+    [0x00, 0x00, 0x00, 0x12, 0x12, 0x0e, 0x12, 0x0c], // 'y'
+    [0x00, 0x00, 0x00, 0x1e, 0x04, 0x08, 0x1e, 0x00], // 'z'
+    [0x06, 0x08, 0x04, 0x18, 0x04, 0x08, 0x06, 0x00], // '{'
+    [0x00, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00], // '|'
+    [0x18, 0x04, 0x08, 0x06, 0x08, 0x04, 0x18, 0x00], // '}'
+    [0x00, 0x0a, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00], // '~'
+];
+
+// the arms of the box drawing glyphs from U+2500 to U+257F, two bits each for up, down, left
+// and right, 0 for the dashed and diagonal glyphs which fall back to '?'
+const BOX_ARMS: [u8; 128] = [
+    0x05, 0x0a, 0x50, 0xa0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x11, 0x12, 0x21, 0x22,
+    0x14, 0x18, 0x24, 0x28, 0x41, 0x42, 0x81, 0x82, 0x44, 0x48, 0x84, 0x88, 0x51, 0x52, 0x91, 0x61,
+    0xa1, 0x92, 0x62, 0xa2, 0x54, 0x58, 0x94, 0x64, 0xa4, 0x98, 0x68, 0xa8, 0x15, 0x19, 0x16, 0x1a,
+    0x25, 0x29, 0x26, 0x2a, 0x45, 0x49, 0x46, 0x4a, 0x85, 0x89, 0x86, 0x8a, 0x55, 0x59, 0x56, 0x5a,
+    0x95, 0x65, 0xa5, 0x99, 0x96, 0x69, 0x66, 0x9a, 0x6a, 0xa9, 0xa6, 0xaa, 0x00, 0x00, 0x00, 0x00,
+    0x0f, 0xf0, 0x13, 0x31, 0x33, 0x1c, 0x34, 0x3c, 0x43, 0xc1, 0xc3, 0x4c, 0xc4, 0xcc, 0x53, 0xf1,
+    0xf3, 0x5c, 0xf4, 0xfc, 0x1f, 0x35, 0x3f, 0x4f, 0xc5, 0xcf, 0x5f, 0xf5, 0xff, 0x11, 0x14, 0x44,
+    0x41, 0x00, 0x00, 0x00, 0x04, 0x40, 0x01, 0x10, 0x08, 0x80, 0x02, 0x20, 0x06, 0x60, 0x09, 0x90,
+];
+
+const NONE: u8 = 0;
+const LIGHT: u8 = 1;
+const HEAVY: u8 = 2;
+const DOUBLE: u8 = 3;
+
+// upper left, upper right, lower left, lower right for the quadrants from U+2596 to U+259F
+const QUADRANTS: [u8; 10] = [0b0100, 0b1000, 0b0001, 0b1101, 0b1001, 0b0111, 0b1011, 0b0010, 0b0110, 0b1110];
+
+// the pixels of a width x height cell that take the foreground colour, row by row,
+// block elements and box drawing are drawn to fit the cell, ascii is stretched to it
+pub(crate) fn glyph_mask(c: char, width: usize, height: usize) -> Vec<bool> {
+    let mut mask = Mask { width, height, bits: vec![false; width * height] };
+    match c {
+        ' ' => {},
+        c if c.is_control() => {},
+        '\u{2500}'..='\u{257f}' if BOX_ARMS[c as usize - 0x2500] != NONE => mask.draw_box(BOX_ARMS[c as usize - 0x2500]),
+        '\u{2580}'..='\u{259f}' => mask.draw_block(c),
+        ' '..='~' => mask.draw_ascii(c),
+        _ => mask.draw_ascii('?')
+    }
+    mask.bits
+}
+
+struct Mask {
+    width: usize,
+    height: usize,
+    bits: Vec<bool>
+}
+
+impl Mask {
+    // clamped to the cell, end exclusive
+    fn fill(&mut self, x1: isize, y1: isize, x2: isize, y2: isize) {
+        let (x1, x2) = (x1.clamp(0, self.width as isize) as usize, x2.clamp(0, self.width as isize) as usize);
+        let (y1, y2) = (y1.clamp(0, self.height as isize) as usize, y2.clamp(0, self.height as isize) as usize);
+        for y in y1..y2 {
+            for bit in &mut self.bits[y * self.width + x1..y * self.width + cmp::max(x1, x2)] {
+                *bit = true;
+            }
+        }
+    }
+    
+    fn draw_ascii(&mut self, c: char) {
+        let rows = &ASCII[c as usize - ' ' as usize];
+        for y in 0..self.height {
+            let row = rows[y * 8 / self.height];
+            for x in 0..self.width {
+                self.bits[y * self.width + x] = row & (0x10 >> (x * 5 / self.width)) != 0;
+            }
+        }
+    }
+    
+    fn draw_block(&mut self, c: char) {
+        let (w, h) = (self.width as isize, self.height as isize);
+        // n eighths of a side, rounded
+        let eighths = |side: isize, n: isize| (side * n + 4) / 8;
+        match c {
+            '\u{2580}' => self.fill(0, 0, w, eighths(h, 4)),
+            '\u{2581}'..='\u{2588}' => self.fill(0, h - eighths(h, c as isize - 0x2580), w, h),
+            '\u{2589}'..='\u{258f}' => self.fill(0, 0, eighths(w, 0x2590 - c as isize), h),
+            '\u{2590}' => self.fill(w - eighths(w, 4), 0, w, h),
+            '\u{2591}'..='\u{2593}' => {
+                // the shades as ordered patterns covering a quarter, half and three quarters of the cell
+                for y in 0..self.height {
+                    for x in 0..self.width {
+                        let (odd_x, odd_y) = (!x.is_multiple_of(2), !y.is_multiple_of(2));
+                        self.bits[y * self.width + x] = match c {
+                            '\u{2591}' => !odd_x && !odd_y,
+                            '\u{2592}' => odd_x == odd_y,
+                            _ => !(odd_x && odd_y)
+                        };
+                    }
+                }
+            },
+            '\u{2594}' => self.fill(0, 0, w, eighths(h, 1)),
+            '\u{2595}' => self.fill(w - eighths(w, 1), 0, w, h),
+            _ => {
+                let quadrants = QUADRANTS[c as usize - 0x2596];
+                let (mx, my) = (eighths(w, 4), eighths(h, 4));
+                if quadrants & 0b0001 != 0 { self.fill(0, 0, mx, my); }
+                if quadrants & 0b0010 != 0 { self.fill(mx, 0, w, my); }
+                if quadrants & 0b0100 != 0 { self.fill(0, my, mx, h); }
+                if quadrants & 0b1000 != 0 { self.fill(mx, my, w, h); }
+            }
+        }
+    }
+    
+    fn draw_box(&mut self, arms: u8) {
+        let (up, down, left, right) = (arms >> 6, arms >> 4 & 3, arms >> 2 & 3, arms & 3);
+        // a light line is one pixel wide up to 15 pixel cells, double lines are two light lines
+        // one light line apart
+        let light = cmp::max(1, cmp::min(self.width, self.height) / 8) as isize;
+        self.draw_arm(false, up, -1, down, (left, right), light);
+        self.draw_arm(false, down, 1, up, (left, right), light);
+        self.draw_arm(true, left, -1, right, (up, down), light);
+        self.draw_arm(true, right, 1, left, (up, down), light);
+    }
+    
+    // an arm goes from the edge of the cell in direction dir (-1 up or left, 1 down or right) to
+    // the lines of the arms across it, sides are the arms across it before and after its centre
+    fn draw_arm(&mut self, horizontal: bool, weight: u8, dir: isize, opposite: u8, sides: (u8, u8), light: isize) {
+        if weight == NONE {
+            return;
+        }
+        let (length, across) = if horizontal { (self.width, self.height) } else { (self.height, self.width) };
+        let (centre, across_centre) = ((length / 2) as isize, (across / 2) as isize);
+        let thickness = |weight: u8| match weight {
+            LIGHT | DOUBLE => light,
+            HEAVY => 2 * light,
+            _ => 0
+        };
+        // the double lines across the arm, near is the one on the arm's side
+        let near = centre + dir * light;
+        let far = centre - dir * light;
+        let mut lines = Vec::new();
+        if weight == DOUBLE {
+            for &(offset, side, other) in [(-light, sides.0, sides.1), (light, sides.1, sides.0)].iter() {
+                let stop = if side == DOUBLE {
+                    (near, light)
+                } else if side != NONE {
+                    (centre, thickness(side))
+                } else if opposite != NONE || other == DOUBLE {
+                    (far, light)
+                } else {
+                    (centre, cmp::max(light, thickness(other)))
+                };
+                lines.push((across_centre + offset, light, stop));
+            }
+        } else if sides.0 == DOUBLE || sides.1 == DOUBLE {
+            // stops on a double line running through, or crosses to the far line of a corner
+            let stop = if sides.0 == DOUBLE && sides.1 == DOUBLE && opposite == NONE { near } else { far };
+            lines.push((across_centre, thickness(weight), (stop, light)));
+        } else {
+            let stop = cmp::max(thickness(weight), cmp::max(thickness(sides.0), thickness(sides.1)));
+            lines.push((across_centre, thickness(weight), (centre, stop)));
+        }
+        for (position, width, (stop, stop_width)) in lines {
+            let (a1, a2) = band(position, width);
+            let (s1, s2) = band(stop, stop_width);
+            let (b1, b2) = if dir < 0 { (0, s2) } else { (s1, length as isize) };
+            if horizontal {
+                self.fill(b1, a1, b2, a2);
+            } else {
+                self.fill(a1, b1, a2, b2);
+            }
+        }
+    }
+}
+
+// the pixels covered by a line of the given width centred on position, end exclusive
+fn band(position: isize, width: isize) -> (isize, isize) {
+    (position - width / 2, position - width / 2 + width)
+}
@@ -0,0 +1,52 @@
+use super::ConsoleBackend;
+
+use std::collections::VecDeque;
+use std::io::Error;
+
+pub struct HeadlessBackend {
+    key_script: VecDeque<Vec<usize>>
+}
+
+impl HeadlessBackend {
+    pub fn new() -> HeadlessBackend {
+        HeadlessBackend {
+            key_script: VecDeque::new()
+        }
+    }
+    
+    // each entry holds the keys that are down during one frame, once the
+    // script runs out every key is released
+    pub fn with_key_script(key_script: Vec<Vec<usize>>) -> HeadlessBackend {
+        HeadlessBackend {
+            key_script: key_script.into_iter().collect()
+        }
+    }
+}
+
+impl Default for HeadlessBackend {
+    fn default() -> HeadlessBackend {
+        HeadlessBackend::new()
+    }
+}
+
+impl ConsoleBackend for HeadlessBackend {
+    fn open(&mut self, _width: usize, _height: usize, _font_width: i16, _font_height: i16) -> Result<(), Error> { Ok(()) }
+    
+    fn resize(&mut self, _width: usize, _height: usize, _font_width: i16, _font_height: i16) -> Result<(), Error> { Ok(()) }
+    
+    fn present(&mut self, _width: usize, _height: usize, _glyphs: &[char], _colors: &[u16]) -> Result<(), Error> { Ok(()) }
+    
+    fn poll_keys(&mut self, key_states: &mut [bool; 256]) -> Result<(), Error> {
+        key_states.iter_mut().for_each(|k| *k = false);
+        if let Some(keys) = self.key_script.pop_front() {
+            for v_key in keys {
+                key_states[v_key] = true;
+            }
+        }
+        Ok(())
+    }
+    
+    fn flush_input(&mut self) -> Result<(), Error> { Ok(()) }
+    
+    fn set_title(&mut self, _title: &str) -> Result<(), Error> { Ok(()) }
+}
@@ -0,0 +1,178 @@
+use super::{RustConsole, RustConsoleSprite, SpriteError};
+use super::palette::{distance, PALETTE};
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ImageCells {
+    // one pixel per cell
+    Pixel,
+    // two pixels side by side per cell, averaged, which keeps the proportions with the usual tall fonts
+    Block2x1
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageOptions {
+    pub cells: ImageCells,
+    // floyd-steinberg, spreads what each cell could not match over its neighbours
+    pub dither: bool,
+    // pixels less opaque than this become transparent ' ' cells
+    pub alpha_threshold: u8
+}
+
+impl Default for ImageOptions {
+    fn default() -> ImageOptions {
+        ImageOptions {
+            cells: ImageCells::Pixel,
+            dither: false,
+            alpha_threshold: 128
+        }
+    }
+}
+
+// a glyph with a fg / bg pair, and the colour it looks like from a distance
+struct Shade {
+    glyph: char,
+    color: u16,
+    rgb: [f32; 3]
+}
+
+fn shades() -> Vec<Shade> {
+    let glyphs = [
+        (RustConsole::PIXEL_SOLID, 1f32),
+        (RustConsole::PIXEL_THREEQUARTER, 0.75f32),
+        (RustConsole::PIXEL_HALF, 0.5f32),
+        (RustConsole::PIXEL_QUARTER, 0.25f32)
+    ];
+    let mut shades = Vec::new();
+    for &(glyph, coverage) in glyphs.iter() {
+        for (fg, fg_rgb) in PALETTE.iter().enumerate() {
+            // a solid cell doesn't show its background, and a shade looks the same as the
+            // opposite shade with fg and bg swapped, so only the pairs with fg > bg are kept
+            let backgrounds = if coverage == 1f32 { 0..1 } else { 0..fg };
+            for bg in backgrounds {
+                let mut rgb = [0f32; 3];
+                for (i, channel) in rgb.iter_mut().enumerate() {
+                    *channel = fg_rgb[i] as f32 * coverage + PALETTE[bg][i] as f32 * (1f32 - coverage);
+                }
+                shades.push(Shade { glyph, color: (fg | bg << 4) as u16, rgb });
+            }
+        }
+    }
+    shades
+}
+
+fn nearest_shade(shades: &[Shade], rgb: [f32; 3]) -> &Shade {
+    let mut best = &shades[0];
+    let mut best_distance = f32::INFINITY;
+    for shade in shades {
+        let distance = distance(rgb, shade.rgb);
+        if distance < best_distance {
+            best = shade;
+            best_distance = distance;
+        }
+    }
+    best
+}
+
+impl RustConsoleSprite {
+    pub fn from_image(path: &str, options: &ImageOptions) -> Result<RustConsoleSprite, SpriteError> {
+        RustConsoleSprite::from_png_reader(BufReader::new(File::open(path)?), options)
+    }
+    
+    pub fn from_png_reader(reader: impl Read, options: &ImageOptions) -> Result<RustConsoleSprite, SpriteError> {
+        let mut decoder = png::Decoder::new(reader);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(png_error)?;
+        let (width, height) = (reader.info().width as usize, reader.info().height as usize);
+        if width.saturating_mul(height) > RustConsoleSprite::MAX_CELLS * 2 {
+            return Err(SpriteError::TooLarge { width, height });
+        }
+        
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut buffer).map_err(png_error)?;
+        let pixels = &buffer[..frame.buffer_size()];
+        let rgba: Vec<u8> = match frame.color_type {
+            png::ColorType::Rgba => pixels.to_vec(),
+            png::ColorType::Rgb => pixels.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+            png::ColorType::GrayscaleAlpha => pixels.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+            png::ColorType::Grayscale => pixels.iter().flat_map(|&p| [p, p, p, 255]).collect(),
+            png::ColorType::Indexed => return Err(SpriteError::Format("indexed png was not expanded".to_string()))
+        };
+        RustConsoleSprite::from_rgba(width, height, &rgba, options)
+    }
+    
+    // pixels are 4 bytes each, red, green, blue and alpha, row by row
+    pub fn from_rgba(width: usize, height: usize, pixels: &[u8], options: &ImageOptions) -> Result<RustConsoleSprite, SpriteError> {
+        let expected = width.saturating_mul(height).saturating_mul(4);
+        if pixels.len() != expected {
+            return Err(SpriteError::Truncated { expected, found: pixels.len() });
+        }
+        let block = match options.cells {
+            ImageCells::Pixel => 1,
+            ImageCells::Block2x1 => 2
+        };
+        let cell_width = width.div_ceil(block);
+        if cell_width.saturating_mul(height) > RustConsoleSprite::MAX_CELLS {
+            return Err(SpriteError::TooLarge { width: cell_width, height });
+        }
+        
+        // average colour of the opaque pixels of each cell, None for transparent cells
+        let mut cells: Vec<Option<[f32; 3]>> = Vec::with_capacity(cell_width * height);
+        for y in 0..height {
+            for cx in 0..cell_width {
+                let mut sum = [0f32; 3];
+                let mut count = 0;
+                for x in cx * block..(cx * block + block).min(width) {
+                    let pixel = &pixels[(y * width + x) * 4..][..4];
+                    if pixel[3] >= options.alpha_threshold {
+                        for i in 0..3 {
+                            sum[i] += pixel[i] as f32;
+                        }
+                        count += 1;
+                    }
+                }
+                cells.push(if count > 0 { Some([sum[0] / count as f32, sum[1] / count as f32, sum[2] / count as f32]) } else { None });
+            }
+        }
+        
+        let shades = shades();
+        let mut sprite = RustConsoleSprite::new(cell_width, height)?;
+        for y in 0..height {
+            for x in 0..cell_width {
+                let rgb = match cells[y * cell_width + x] {
+                    Some(rgb) => [rgb[0].clamp(0f32, 255f32), rgb[1].clamp(0f32, 255f32), rgb[2].clamp(0f32, 255f32)],
+                    None => continue
+                };
+                let shade = nearest_shade(&shades, rgb);
+                sprite.set_glyph(x, y, shade.glyph);
+                sprite.set_color(x, y, shade.color);
+                
+                if options.dither {
+                    let error = [rgb[0] - shade.rgb[0], rgb[1] - shade.rgb[1], rgb[2] - shade.rgb[2]];
+                    let neighbours = [(1isize, 0usize, 7f32 / 16f32), (-1, 1, 3f32 / 16f32), (0, 1, 5f32 / 16f32), (1, 1, 1f32 / 16f32)];
+                    for &(dx, dy, weight) in neighbours.iter() {
+                        let (nx, ny) = (x as isize + dx, y + dy);
+                        if nx < 0 || nx as usize >= cell_width || ny >= height {
+                            continue;
+                        }
+                        if let Some(neighbour) = &mut cells[ny * cell_width + nx as usize] {
+                            for i in 0..3 {
+                                neighbour[i] += error[i] * weight;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(sprite)
+    }
+}
+
+fn png_error(error: png::DecodingError) -> SpriteError {
+    match error {
+        png::DecodingError::IoError(error) => SpriteError::Io(error),
+        error => SpriteError::Format(format!("invalid png: {}", error))
+    }
+}
@@ -1,14 +1,86 @@
-#[cfg_attr(target_os = "windows", path = "winconsole.rs")]
-#[cfg_attr(not(target_os = "windows"), path = "noconsole.rs")]
 mod console;
 pub use console::RustConsole;
 
+mod rect;
+pub use rect::Rect;
+
+mod text;
+pub use text::{measure_text, TextAlign};
+
+mod blit;
+pub use blit::{BlitOptions, Transparency};
+
+mod transform;
+pub use transform::Transform2D;
+
+mod draw;
+pub use draw::DrawTarget;
+
+mod backend;
+pub use backend::{default_backend, ConsoleBackend};
+
+#[cfg(target_os = "windows")]
+mod winconsole;
+#[cfg(target_os = "windows")]
+pub use winconsole::WinConsoleBackend;
+
+#[cfg(not(target_os = "windows"))]
+mod termconsole;
+#[cfg(not(target_os = "windows"))]
+pub use termconsole::TerminalBackend;
+
+mod headless;
+pub use headless::HeadlessBackend;
+
 mod engine;
-pub use engine::RustConsoleGameEngine;
+pub use engine::{RustConsoleGameEngine, TitleFormat, TitlePolicy};
+
+mod error;
+pub use error::{AnimationError, EngineError, SpriteError, TiledError};
+
+mod stats;
+pub use stats::FrameStats;
+
+mod scene;
+pub use scene::{Scene, SceneStack, SceneTransition};
 
 mod sprite;
 pub use sprite::RustConsoleSprite;
 
+mod palette;
+pub use palette::{nearest_color, PALETTE};
+
+mod image;
+pub use image::{ImageCells, ImageOptions};
+
+mod font;
+
+mod cp437;
+
+mod export;
+
+mod xp;
+
+mod ansi;
+pub use ansi::Sauce;
+
+mod plaintext;
+pub use plaintext::ColorKey;
+
+mod animation;
+pub use animation::{AnimatedSprite, Animation, Clip, Playback};
+
+mod tilemap;
+pub use tilemap::{DrawTileMap, Tile, TileGraphic, TileLayer, TileMap};
+
+mod tiled;
+pub use tiled::{TiledLoader, TiledMap, TiledObject, TiledProperties, TiledProperty};
+
+mod snapshot;
+pub use snapshot::{assert_snapshot, run_frames, snapshot_text};
+
+use std::error::Error;
+
 #[derive(Copy, Clone)]
 pub struct KeyState {
     pub pressed: bool,
@@ -18,6 +90,10 @@ pub struct KeyState {
 
 pub trait RustConsoleGame {
     fn name(&self) -> &str;
-    fn setup(&mut self);
-    fn update(&mut self, console: &mut RustConsole, elapsed_time: f32);
+    fn setup(&mut self) -> Result<(), Box<dyn Error>>;
+    fn update(&mut self, console: &mut RustConsole, elapsed_time: f32) -> Result<(), Box<dyn Error>>;
+    // alpha is how far the current frame is between the last update and the next one,
+    // it is always 1 without a fixed timestep
+    fn render(&mut self, _console: &mut RustConsole, _alpha: f32) -> Result<(), Box<dyn Error>> { Ok(()) }
+    fn on_destroy(&mut self) -> Result<(), Box<dyn Error>> { Ok(()) }
 }
\ No newline at end of file
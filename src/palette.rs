@@ -0,0 +1,42 @@
+// the ansi colour numbers, black red green yellow blue magenta cyan white, as FG_* values, the console's
+// colours are bgr and the ansi ones rgb, so the same table turns FG_* values back into ansi numbers
+pub(crate) const ANSI_COLORS: [u16; 8] = [0, 4, 2, 6, 1, 5, 3, 7];
+
+// the classic colours of the windows console, indexed by the FG_* values
+pub const PALETTE: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [0, 0, 128],
+    [0, 128, 0],
+    [0, 128, 128],
+    [128, 0, 0],
+    [128, 0, 128],
+    [128, 128, 0],
+    [192, 192, 192],
+    [128, 128, 128],
+    [0, 0, 255],
+    [0, 255, 0],
+    [0, 255, 255],
+    [255, 0, 0],
+    [255, 0, 255],
+    [255, 255, 0],
+    [255, 255, 255]
+];
+
+// index of the palette colour closest to rgb, the first one wins a tie
+pub fn nearest_color(rgb: [u8; 3]) -> u16 {
+    let rgb = [rgb[0] as f32, rgb[1] as f32, rgb[2] as f32];
+    let mut best = 0;
+    let mut best_distance = f32::INFINITY;
+    for (i, color) in PALETTE.iter().enumerate() {
+        let distance = distance(rgb, [color[0] as f32, color[1] as f32, color[2] as f32]);
+        if distance < best_distance {
+            best = i;
+            best_distance = distance;
+        }
+    }
+    best as u16
+}
+
+pub(crate) fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
@@ -0,0 +1,117 @@
+use super::{RustConsole, RustConsoleSprite, SpriteError};
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+const TAB_WIDTH: usize = 8;
+const COLOR_NAMES: [&str; 16] = [
+    "black", "dark_blue", "dark_green", "dark_cyan", "dark_red", "dark_magenta", "dark_yellow", "grey",
+    "dark_grey", "blue", "green", "cyan", "red", "magenta", "yellow", "white"
+];
+
+// the colour of each glyph of a text file, glyphs that aren't in the key are white, and ' ' is
+// left blank like the cells of a new sprite
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ColorKey {
+    colors: HashMap<char, u16>
+}
+
+impl ColorKey {
+    pub fn new() -> ColorKey {
+        ColorKey { colors: HashMap::new() }
+    }
+
+    pub fn set(mut self, c: char, col: u16) -> ColorKey {
+        self.colors.insert(c, col);
+        self
+    }
+
+    pub fn get(&self, c: char) -> u16 {
+        match self.colors.get(&c) {
+            Some(&col) => col,
+            None if c == ' ' => RustConsole::FG_BLACK,
+            None => RustConsole::FG_WHITE
+        }
+    }
+
+    pub fn from_path(path: &str) -> Result<ColorKey, SpriteError> {
+        ColorKey::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    // one glyph per line, then its foreground and optionally its background, by name like
+    // dark_red or by number from 0 to 15, so "# grey dark_blue", lines starting with // are comments
+    pub fn from_reader(reader: impl Read) -> Result<ColorKey, SpriteError> {
+        let mut key = ColorKey::new();
+        for (number, line) in read_text(reader)?.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with("//") {
+                continue;
+            }
+            let mut chars = line.chars();
+            let c = chars.next().unwrap();
+            let colors: Vec<&str> = chars.as_str().split_whitespace().collect();
+            let error = |message: String| SpriteError::Format(format!("line {} of the colour key: {}", number + 1, message));
+            if colors.is_empty() || colors.len() > 2 || !chars.as_str().starts_with(char::is_whitespace) {
+                return Err(error(format!("expected a glyph and one or two colours, found '{}'", line)));
+            }
+            let mut col = 0;
+            for (i, name) in colors.iter().enumerate() {
+                let color = match COLOR_NAMES.iter().position(|color| color.eq_ignore_ascii_case(name)) {
+                    Some(color) => color as u16,
+                    None => match name.parse::<u16>() {
+                        Ok(color) if color < 16 => color,
+                        _ => return Err(error(format!("unknown colour '{}'", name)))
+                    }
+                };
+                col |= color << (4 * i);
+            }
+            key.colors.insert(c, col);
+        }
+        Ok(key)
+    }
+}
+
+impl RustConsoleSprite {
+    pub fn from_text(path: &str, key: Option<&ColorKey>) -> Result<RustConsoleSprite, SpriteError> {
+        RustConsoleSprite::from_text_reader(BufReader::new(File::open(path)?), key)
+    }
+
+    // one row per line of utf-8 text, as wide as the longest line, tabs stop every 8 columns
+    pub fn from_text_reader(reader: impl Read, key: Option<&ColorKey>) -> Result<RustConsoleSprite, SpriteError> {
+        let text = read_text(reader)?;
+        let rows: Vec<Vec<char>> = text.lines().map(|line| {
+            let mut row = Vec::new();
+            for c in line.chars() {
+                if c == '\t' {
+                    row.resize((row.len() / TAB_WIDTH + 1) * TAB_WIDTH, ' ');
+                } else {
+                    row.push(c);
+                }
+            }
+            row
+        }).collect();
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        let height = rows.len();
+        if width.saturating_mul(height) > RustConsoleSprite::MAX_CELLS {
+            return Err(SpriteError::TooLarge { width, height });
+        }
+
+        let default = ColorKey::new();
+        let key = key.unwrap_or(&default);
+        let mut glyphs = Vec::with_capacity(width * height);
+        for mut row in rows {
+            row.resize(width, ' ');
+            glyphs.extend(row);
+        }
+        let colors = glyphs.iter().map(|&c| key.get(c)).collect();
+        Ok(RustConsoleSprite::from_cells(width, height, glyphs, colors))
+    }
+}
+
+// the whole text without a byte order mark
+fn read_text(mut reader: impl Read) -> Result<String, SpriteError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let text = String::from_utf8(bytes).map_err(|error| SpriteError::Format(format!("text is not valid utf-8: {}", error)))?;
+    Ok(text.strip_prefix('\u{feff}').unwrap_or(&text).to_string())
+}
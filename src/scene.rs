@@ -0,0 +1,124 @@
+use super::{RustConsole, RustConsoleGame};
+
+use std::error::Error;
+
+pub enum SceneTransition {
+    None,
+    Push(Box<dyn Scene>),
+    Pop,
+    Replace(Box<dyn Scene>),
+    Quit
+}
+
+pub trait Scene {
+    fn enter(&mut self) -> Result<(), Box<dyn Error>> { Ok(()) }
+    fn exit(&mut self) -> Result<(), Box<dyn Error>> { Ok(()) }
+    // another scene was pushed on top of this one, or popped off it
+    fn pause(&mut self) -> Result<(), Box<dyn Error>> { Ok(()) }
+    fn resume(&mut self) -> Result<(), Box<dyn Error>> { Ok(()) }
+    fn update(&mut self, console: &mut RustConsole, elapsed_time: f32) -> Result<SceneTransition, Box<dyn Error>>;
+    fn render(&mut self, console: &mut RustConsole, alpha: f32) -> Result<(), Box<dyn Error>>;
+    // the scenes below a transparent scene are rendered before it, e.g. under a pause overlay
+    fn transparent(&self) -> bool { false }
+}
+
+// only the top scene is updated, the engine quits when the last scene is popped
+pub struct SceneStack {
+    name: String,
+    initial: Option<Box<dyn Scene>>,
+    scenes: Vec<Box<dyn Scene>>
+}
+
+impl SceneStack {
+    pub fn new(name: &str, initial: Box<dyn Scene>) -> SceneStack {
+        SceneStack {
+            name: name.to_string(),
+            initial: Some(initial),
+            scenes: Vec::new()
+        }
+    }
+    
+    pub fn len(&self) -> usize { self.scenes.len() }
+    
+    pub fn is_empty(&self) -> bool { self.scenes.is_empty() }
+    
+    fn push(&mut self, mut scene: Box<dyn Scene>) -> Result<(), Box<dyn Error>> {
+        if let Some(top) = self.scenes.last_mut() {
+            top.pause()?;
+        }
+        scene.enter()?;
+        self.scenes.push(scene);
+        Ok(())
+    }
+    
+    fn pop(&mut self) -> Result<(), Box<dyn Error>> {
+        if let Some(mut top) = self.scenes.pop() {
+            top.exit()?;
+        }
+        if let Some(top) = self.scenes.last_mut() {
+            top.resume()?;
+        }
+        Ok(())
+    }
+    
+    fn replace(&mut self, mut scene: Box<dyn Scene>) -> Result<(), Box<dyn Error>> {
+        if let Some(mut top) = self.scenes.pop() {
+            top.exit()?;
+        }
+        scene.enter()?;
+        self.scenes.push(scene);
+        Ok(())
+    }
+}
+
+impl RustConsoleGame for SceneStack {
+    fn name(&self) -> &str { &self.name }
+    
+    fn setup(&mut self) -> Result<(), Box<dyn Error>> {
+        if let Some(initial) = self.initial.take() {
+            self.push(initial)?;
+        }
+        Ok(())
+    }
+    
+    fn update(&mut self, console: &mut RustConsole, elapsed_time: f32) -> Result<(), Box<dyn Error>> {
+        let transition = match self.scenes.last_mut() {
+            Some(top) => top.update(console, elapsed_time)?,
+            None => SceneTransition::Quit
+        };
+        
+        match transition {
+            SceneTransition::None => {},
+            SceneTransition::Push(scene) => self.push(scene)?,
+            SceneTransition::Pop => self.pop()?,
+            SceneTransition::Replace(scene) => self.replace(scene)?,
+            SceneTransition::Quit => console.quit()
+        }
+        
+        if self.scenes.is_empty() {
+            console.quit();
+        }
+        Ok(())
+    }
+    
+    fn render(&mut self, console: &mut RustConsole, alpha: f32) -> Result<(), Box<dyn Error>> {
+        let mut bottom = self.scenes.len();
+        while bottom > 0 {
+            bottom -= 1;
+            if !self.scenes[bottom].transparent() {
+                break;
+            }
+        }
+        for scene in self.scenes.iter_mut().skip(bottom) {
+            scene.render(console, alpha)?;
+        }
+        Ok(())
+    }
+    
+    fn on_destroy(&mut self) -> Result<(), Box<dyn Error>> {
+        while let Some(mut top) = self.scenes.pop() {
+            top.exit()?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use super::{HeadlessBackend, RustConsoleGame, RustConsoleGameEngine, RustConsoleSprite};
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+// runs the game headless with a fixed elapsed time, the key script holds the
+// keys that are down during each frame
+pub fn run_frames(game: &mut dyn RustConsoleGame, width: usize, height: usize, frames: usize, elapsed_time: f32, key_script: Vec<Vec<usize>>) -> RustConsoleSprite {
+    let backend = Box::new(HeadlessBackend::with_key_script(key_script));
+    let mut engine = RustConsoleGameEngine::with_backend(game, backend, width, height, 8, 8).unwrap_or_else(|error| {
+        panic!("Error creating headless console: {:?}", error);
+    });
+    engine.start().unwrap_or_else(|error| {
+        panic!("Error starting headless console: {:?}", error);
+    });
+    for _ in 0..frames {
+        if engine.quit_requested() {
+            break;
+        }
+        engine.step(elapsed_time).unwrap_or_else(|error| {
+            panic!("Error running headless frame: {:?}", error);
+        });
+    }
+    engine.console().to_sprite()
+}
+
+// glyph layer, then color layer with two hex digits (background, foreground) per cell,
+// every row is closed with a '|' so that trailing spaces survive editors
+pub fn snapshot_text(sprite: &RustConsoleSprite) -> String {
+    let mut text = String::new();
+    writeln!(text, "glyphs {}x{}", sprite.width(), sprite.height()).unwrap();
+    for y in 0..sprite.height() {
+        for x in 0..sprite.width() {
+            let c = sprite.get_glyph(x, y);
+            text.push(if c.is_control() { ' ' } else { c });
+        }
+        text.push_str("|\n");
+    }
+    text.push_str("colors\n");
+    for y in 0..sprite.height() {
+        for x in 0..sprite.width() {
+            write!(text, "{:02x}", sprite.get_color(x, y) & 0x00ff).unwrap();
+        }
+        text.push_str("|\n");
+    }
+    text
+}
+
+fn snapshot_diff(expected: &str, actual: &str) -> String {
+    let expected_lines = expected.lines().collect::<Vec<&str>>();
+    let actual_lines = actual.lines().collect::<Vec<&str>>();
+    let mut diff = String::new();
+    for i in 0..expected_lines.len().max(actual_lines.len()) {
+        let e = expected_lines.get(i).copied().unwrap_or("");
+        let a = actual_lines.get(i).copied().unwrap_or("");
+        if e != a {
+            let markers = e.chars().map(Some).chain(std::iter::repeat(None))
+                .zip(a.chars().map(Some).chain(std::iter::repeat(None)))
+                .take(e.chars().count().max(a.chars().count()))
+                .map(|(ec, ac)| if ec == ac { ' ' } else { '^' })
+                .collect::<String>();
+            writeln!(diff, "line {}:", i + 1).unwrap();
+            writeln!(diff, "- {}", e).unwrap();
+            writeln!(diff, "+ {}", a).unwrap();
+            writeln!(diff, "  {}", markers.trim_end()).unwrap();
+        }
+    }
+    diff
+}
+
+// compares against a checked-in snapshot, run the tests with UPDATE_SNAPSHOTS=1
+// to write the current frames instead
+pub fn assert_snapshot<P: AsRef<Path>>(sprite: &RustConsoleSprite, path: P) {
+    let path = path.as_ref();
+    let actual = snapshot_text(sprite);
+    
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap_or_else(|error| {
+                panic!("Error creating snapshot directory {}: {:?}", parent.display(), error);
+            });
+        }
+        fs::write(path, &actual).unwrap_or_else(|error| {
+            panic!("Error writing snapshot {}: {:?}", path.display(), error);
+        });
+        return;
+    }
+    
+    let expected = fs::read_to_string(path).unwrap_or_else(|error| {
+        panic!("Error reading snapshot {} (run with UPDATE_SNAPSHOTS=1 to create it): {:?}", path.display(), error);
+    });
+    if expected.replace("\r\n", "\n") != actual {
+        panic!("Snapshot {} does not match (run with UPDATE_SNAPSHOTS=1 to update it):\n{}", path.display(), snapshot_diff(&expected, &actual));
+    }
+}
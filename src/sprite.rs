@@ -1,9 +1,16 @@
 use super::RustConsole;
+use super::draw::DrawTarget;
 
-use std::{convert::TryInto, io::Error, mem::size_of};
-use std::io::Read;
+use super::SpriteError;
+
+use std::{convert::TryFrom, convert::TryInto, io::Error, mem::size_of};
+use std::io::{BufReader, BufWriter, Read, Write};
 use std::fs::File;
 
+// width and height as u32
+const HEADER_SIZE: usize = 2 * size_of::<u32>();
+
+#[derive(Clone)]
 pub struct RustConsoleSprite {
     width: usize,
     height: usize,
@@ -12,6 +19,10 @@ pub struct RustConsoleSprite {
 }
 
 impl RustConsoleSprite {
+    // a budget of cells rather than a size, 4096x4096 fits and so does 65536x256, files claiming
+    // more are rejected before anything is allocated
+    pub const MAX_CELLS: usize = 1 << 24;
+    
     pub fn new(w: usize, h: usize) -> Result<RustConsoleSprite, Error> {
         Ok(RustConsoleSprite {
             width: w,
@@ -21,25 +32,81 @@ impl RustConsoleSprite {
         })
     }
 
-    pub fn from_path(path: &str) -> Result<RustConsoleSprite, Error> {
-        let mut f = File::open(path)?;
-        let mut buffer = [0; size_of::<u32>()];
-        f.read_exact(&mut buffer)?;
-        let w = u32::from_le_bytes(buffer) as usize;
-        f.read_exact(&mut buffer)?;
-        let h = u32::from_le_bytes(buffer) as usize;
-        let mut colors = vec![0; size_of::<u16>() * w * h];
-        f.read_exact(&mut colors)?;
-        let mut glyphs = vec![0; size_of::<u16>() * w * h];
-        f.read_exact(&mut glyphs)?;
+    pub(crate) fn from_cells(width: usize, height: usize, glyphs: Vec<char>, colors: Vec<u16>) -> RustConsoleSprite {
+        RustConsoleSprite {
+            width,
+            height,
+            glyphs,
+            colors
+        }
+    }
+
+    pub fn from_path(path: &str) -> Result<RustConsoleSprite, SpriteError> {
+        RustConsoleSprite::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    // the OLC .spr layout, u32 width and height, then all colours, then all glyphs as u16, little endian
+    pub fn from_reader(mut reader: impl Read) -> Result<RustConsoleSprite, SpriteError> {
+        let header = read_up_to(&mut reader, HEADER_SIZE)?;
+        if header.len() < HEADER_SIZE {
+            return Err(SpriteError::Truncated { expected: HEADER_SIZE, found: header.len() });
+        }
+        let w = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+        let h = u32::from_le_bytes(header[4..].try_into().unwrap()) as usize;
+        let cells = match w.checked_mul(h) {
+            Some(cells) if cells <= RustConsoleSprite::MAX_CELLS => cells,
+            _ => return Err(SpriteError::TooLarge { width: w, height: h })
+        };
+        
+        // the data is read as it comes rather than allocated up front from the header
+        let expected = HEADER_SIZE + 2 * size_of::<u16>() * cells;
+        let data = read_up_to(&mut reader, expected - HEADER_SIZE)?;
+        if data.len() < expected - HEADER_SIZE {
+            return Err(SpriteError::Truncated { expected, found: HEADER_SIZE + data.len() });
+        }
+        if reader.read(&mut [0u8])? > 0 {
+            return Err(SpriteError::TrailingBytes);
+        }
+        
+        let (colors, glyphs) = data.split_at(size_of::<u16>() * cells);
+        let glyphs = glyphs.chunks_exact(2).enumerate().map(|(i, c)| {
+            let code = u16::from_le_bytes([c[0], c[1]]) as u32;
+            char::from_u32(code).ok_or(SpriteError::InvalidGlyph { x: i % w, y: i / w, code })
+        }).collect::<Result<Vec<char>, SpriteError>>()?;
         Ok(RustConsoleSprite {
             width: w,
             height: h,
-            glyphs: glyphs.chunks_exact(2).map(|c| char::from_u32(u16::from_le_bytes(c[..2].try_into().unwrap()) as u32).unwrap()).collect(),
-            colors: colors.chunks_exact(2).map(|c| u16::from_le_bytes(c[..2].try_into().unwrap())).collect()
+            glyphs,
+            colors: colors.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect()
         })
     }
 
+    pub fn save(&self, path: &str) -> Result<(), SpriteError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_to(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    // writes the same layout from_reader reads, so a sprite that was read is written back byte for byte
+    pub fn write_to(&self, mut writer: impl Write) -> Result<(), SpriteError> {
+        if self.glyphs.len() > RustConsoleSprite::MAX_CELLS || u32::try_from(self.width).is_err() || u32::try_from(self.height).is_err() {
+            return Err(SpriteError::TooLarge { width: self.width, height: self.height });
+        }
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + 2 * size_of::<u16>() * self.glyphs.len());
+        bytes.extend_from_slice(&(self.width as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.height as u32).to_le_bytes());
+        for col in &self.colors {
+            bytes.extend_from_slice(&col.to_le_bytes());
+        }
+        for (i, &c) in self.glyphs.iter().enumerate() {
+            let glyph = u16::try_from(c as u32).map_err(|_| SpriteError::InvalidGlyph { x: i % self.width, y: i / self.width, code: c as u32 })?;
+            bytes.extend_from_slice(&glyph.to_le_bytes());
+        }
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+
     pub fn width(&self) -> usize { self.width }
     
     pub fn height(&self) -> usize { self.height }
@@ -58,17 +125,17 @@ impl RustConsoleSprite {
 
     pub fn get_glyph(&self, x: usize, y: usize) -> char {
         if !(x >= self.width || y >= self.height) {
-            return self.glyphs[y * self.width + x];
+            self.glyphs[y * self.width + x]
         } else {
-            return ' ';
+            ' '
         }
     }
 
     pub fn get_color(&self, x: usize, y: usize) -> u16 {
         if !(x >= self.width || y >= self.height) {
-            return self.colors[y * self.width + x];
+            self.colors[y * self.width + x]
         } else {
-            return RustConsole::FG_BLACK;
+            RustConsole::FG_BLACK
         }
     }
 
@@ -76,9 +143,9 @@ impl RustConsoleSprite {
         let sx = (x * self.width as f32) as isize;
         let sy = (y * self.height as f32 - 1f32) as isize;
         if !(sx < 0  || sx >= self.width as isize || sy < 0 || sy >= self.height as isize) {
-            return self.glyphs[sy as usize * self.width + sx as usize];
+            self.glyphs[sy as usize * self.width + sx as usize]
         } else {
-            return ' ';
+            ' '
         }
     }
 
@@ -86,9 +153,29 @@ impl RustConsoleSprite {
         let sx = (x * self.width as f32) as isize;
         let sy = (y * self.height as f32 - 1f32) as isize;
         if !(sx < 0  || sx >= self.width as isize || sy < 0 || sy >= self.height as isize) {
-            return self.colors[sy as usize * self.width + sx as usize];
+            self.colors[sy as usize * self.width + sx as usize]
         } else {
-            return RustConsole::FG_BLACK;
+            RustConsole::FG_BLACK
+        }
+    }
+}
+
+impl DrawTarget for RustConsoleSprite {
+    fn width(&self) -> usize { self.width }
+    
+    fn height(&self) -> usize { self.height }
+    
+    fn set_cell(&mut self, x: usize, y: usize, c: char, col: u16) {
+        if x < self.width && y < self.height {
+            self.glyphs[y * self.width + x] = c;
+            self.colors[y * self.width + x] = col;
         }
     }
-}
\ No newline at end of file
+}
+
+// at most len bytes, fewer when the reader runs out first
+pub(crate) fn read_up_to(reader: &mut impl Read, len: usize) -> Result<Vec<u8>, Error> {
+    let mut data = Vec::new();
+    reader.take(len as u64).read_to_end(&mut data)?;
+    Ok(data)
+}
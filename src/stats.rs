@@ -0,0 +1,60 @@
+// frame times are in seconds, over the last FRAME_WINDOW frames
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FrameStats {
+    pub min: f32,
+    pub avg: f32,
+    pub max: f32,
+    // average of the slowest 1% frames
+    pub low_1_percent: f32
+}
+
+impl FrameStats {
+    pub fn fps(&self) -> f32 {
+        if self.avg > 0f32 { 1f32 / self.avg } else { 0f32 }
+    }
+    
+    pub fn low_1_percent_fps(&self) -> f32 {
+        if self.low_1_percent > 0f32 { 1f32 / self.low_1_percent } else { 0f32 }
+    }
+}
+
+const FRAME_WINDOW: usize = 500;
+
+pub(crate) struct FrameTimes {
+    times: Vec<f32>,
+    next: usize,
+    sorted: Vec<f32>
+}
+
+impl FrameTimes {
+    pub(crate) fn new() -> FrameTimes {
+        FrameTimes {
+            times: Vec::with_capacity(FRAME_WINDOW),
+            next: 0,
+            sorted: Vec::with_capacity(FRAME_WINDOW)
+        }
+    }
+    
+    pub(crate) fn push(&mut self, frame_time: f32) -> FrameStats {
+        if self.times.len() < FRAME_WINDOW {
+            self.times.push(frame_time);
+        } else {
+            self.times[self.next] = frame_time;
+        }
+        self.next = (self.next + 1) % FRAME_WINDOW;
+        
+        let n = self.times.len();
+        let min = self.times.iter().cloned().fold(f32::MAX, f32::min);
+        let max = self.times.iter().cloned().fold(0f32, f32::max);
+        let avg = self.times.iter().sum::<f32>() / n as f32;
+        
+        // only the slowest 1% need to be in order
+        let slowest = (n / 100).max(1);
+        self.sorted.clear();
+        self.sorted.extend_from_slice(&self.times);
+        self.sorted.select_nth_unstable_by(n - slowest, |a, b| a.total_cmp(b));
+        let low_1_percent = self.sorted[n - slowest..].iter().sum::<f32>() / slowest as f32;
+        
+        FrameStats { min, avg, max, low_1_percent }
+    }
+}
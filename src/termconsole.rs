@@ -0,0 +1,295 @@
+use super::{ConsoleBackend, RustConsole};
+use super::palette::ANSI_COLORS;
+
+use std::io::{stdout, Error, Write};
+use std::mem::MaybeUninit;
+use std::panic;
+use std::sync::{Once, OnceLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use libc::{
+    c_int,
+    c_void,
+    ioctl,
+    read,
+    write,
+    raise,
+    signal,
+    sighandler_t,
+    tcgetattr,
+    tcsetattr,
+    tcflush,
+    cfmakeraw,
+    termios,
+    winsize,
+    ISIG,
+    VMIN,
+    VTIME,
+    TCSAFLUSH,
+    TCIFLUSH,
+    TIOCGWINSZ,
+    STDIN_FILENO,
+    STDOUT_FILENO,
+    SIGINT,
+    SIGTERM,
+    SIGHUP,
+    SIG_DFL
+};
+
+// alternate screen, hidden cursor, no autowrap
+const ENTER_SEQUENCE: &[u8] = b"\x1b[?1049h\x1b[?25l\x1b[?7l\x1b[2J";
+const RESTORE_SEQUENCE: &[u8] = b"\x1b[0m\x1b[?7h\x1b[?25h\x1b[?1049l";
+
+// terminals only report key presses, so a key is considered held until it stops
+// repeating ; the first repeat comes after the keyboard delay, the next ones faster
+const KEY_HOLD_DELAY: Duration = Duration::from_millis(550);
+const KEY_REPEAT_DELAY: Duration = Duration::from_millis(80);
+
+const VK_BACK: usize = 0x08;
+const VK_TAB: usize = 0x09;
+const VK_RETURN: usize = 0x0d;
+const VK_SHIFT: usize = 0x10;
+const VK_CONTROL: usize = 0x11;
+const VK_SPACE: usize = 0x20;
+
+static ORIGINAL_TERMIOS: OnceLock<termios> = OnceLock::new();
+static TERMINAL_ACTIVE: AtomicBool = AtomicBool::new(false);
+static INSTALL_HOOKS: Once = Once::new();
+
+// only async-signal-safe calls in here, it also runs from the signal handler
+fn restore_terminal() {
+    if TERMINAL_ACTIVE.swap(false, Ordering::SeqCst) {
+        if let Some(original) = ORIGINAL_TERMIOS.get() {
+            unsafe {
+                write(STDOUT_FILENO, RESTORE_SEQUENCE.as_ptr() as *const c_void, RESTORE_SEQUENCE.len());
+                tcsetattr(STDIN_FILENO, TCSAFLUSH, original);
+            }
+        }
+    }
+}
+
+extern "C" fn handle_signal(sig: c_int) {
+    restore_terminal();
+    unsafe {
+        signal(sig, SIG_DFL);
+        raise(sig);
+    }
+}
+
+fn install_hooks() {
+    INSTALL_HOOKS.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            previous_hook(info);
+        }));
+        
+        let handler = handle_signal as extern "C" fn(c_int) as sighandler_t;
+        unsafe {
+            signal(SIGINT, handler);
+            signal(SIGTERM, handler);
+            signal(SIGHUP, handler);
+        }
+    });
+}
+
+fn check_terminal_size(width: usize, height: usize) -> Result<(), Error> {
+    let mut ws = unsafe { MaybeUninit::<winsize>::zeroed().assume_init() };
+    let ret = unsafe { ioctl(STDOUT_FILENO, TIOCGWINSZ, &mut ws) };
+    if ret == -1 { return Err(Error::last_os_error()); }
+    if width > ws.ws_col as usize {
+        return Err(Error::other("Width too big for terminal"));
+    }
+    if height > ws.ws_row as usize {
+        return Err(Error::other("Height too big for terminal"));
+    }
+    Ok(())
+}
+
+fn sgr(col: u16) -> String {
+    let fg = (col & 0x000f) as usize;
+    let bg = ((col & 0x00f0) >> 4) as usize;
+    let fg_base = if fg & 0x8 != 0 { 90 } else { 30 };
+    let bg_base = if bg & 0x8 != 0 { 100 } else { 40 };
+    format!("\x1b[{};{}m", fg_base + ANSI_COLORS[fg & 0x7], bg_base + ANSI_COLORS[bg & 0x7])
+}
+
+pub struct TerminalBackend {
+    output: Vec<u8>,
+    key_last_seen: [Option<Instant>; 256],
+    key_repeating: [bool; 256]
+}
+
+impl TerminalBackend {
+    pub fn new() -> TerminalBackend {
+        TerminalBackend {
+            output: Vec::new(),
+            key_last_seen: [None; 256],
+            key_repeating: [false; 256]
+        }
+    }
+    
+    fn read_input(&mut self) -> Vec<usize> {
+        let mut bytes = Vec::new();
+        let mut buffer = [0u8; 64];
+        loop {
+            let ret = unsafe { read(STDIN_FILENO, buffer.as_mut_ptr() as *mut c_void, buffer.len()) };
+            if ret <= 0 { break; }
+            bytes.extend_from_slice(&buffer[..ret as usize]);
+        }
+        
+        let mut v_keys = Vec::new();
+        let mut i = 0;
+        while i < bytes.len() {
+            let b = bytes[i];
+            i += 1;
+            match b {
+                0x1b if i < bytes.len() && (bytes[i] == b'[' || bytes[i] == b'O') => {
+                    // escape sequence, skip parameters up to the final byte
+                    i += 1;
+                    while i < bytes.len() && !(0x40..=0x7e).contains(&bytes[i]) {
+                        i += 1;
+                    }
+                    if i < bytes.len() {
+                        match bytes[i] {
+                            b'A' => v_keys.push(RustConsole::VK_UP as usize),
+                            b'B' => v_keys.push(RustConsole::VK_DOWN as usize),
+                            b'C' => v_keys.push(RustConsole::VK_RIGHT as usize),
+                            b'D' => v_keys.push(RustConsole::VK_LEFT as usize),
+                            _ => {}
+                        }
+                        i += 1;
+                    }
+                },
+                0x1b => v_keys.push(RustConsole::VK_ESCAPE as usize),
+                b'\r' | b'\n' => v_keys.push(VK_RETURN),
+                b'\t' => v_keys.push(VK_TAB),
+                0x08 | 0x7f => v_keys.push(VK_BACK),
+                0x01..=0x1a => {
+                    v_keys.push(VK_CONTROL);
+                    v_keys.push((b'A' + b - 1) as usize);
+                },
+                b' ' => v_keys.push(VK_SPACE),
+                b'a'..=b'z' => v_keys.push(b.to_ascii_uppercase() as usize),
+                b'A'..=b'Z' => {
+                    v_keys.push(VK_SHIFT);
+                    v_keys.push(b as usize);
+                },
+                b'0'..=b'9' => v_keys.push(b as usize),
+                _ => {}
+            }
+        }
+        v_keys
+    }
+}
+
+impl Default for TerminalBackend {
+    fn default() -> TerminalBackend {
+        TerminalBackend::new()
+    }
+}
+
+impl ConsoleBackend for TerminalBackend {
+    fn open(&mut self, width: usize, height: usize, _font_width: i16, _font_height: i16) -> Result<(), Error> {
+        check_terminal_size(width, height)?;
+        
+        let mut raw = unsafe { MaybeUninit::<termios>::zeroed().assume_init() };
+        let ret = unsafe { tcgetattr(STDIN_FILENO, &mut raw) };
+        if ret == -1 { return Err(Error::last_os_error()); }
+        ORIGINAL_TERMIOS.get_or_init(|| raw);
+        
+        install_hooks();
+        
+        // raw input, but keep ctrl-c working, and never block on reads
+        unsafe { cfmakeraw(&mut raw) };
+        raw.c_lflag |= ISIG;
+        raw.c_cc[VMIN] = 0;
+        raw.c_cc[VTIME] = 0;
+        let ret = unsafe { tcsetattr(STDIN_FILENO, TCSAFLUSH, &raw) };
+        if ret == -1 { return Err(Error::last_os_error()); }
+        TERMINAL_ACTIVE.store(true, Ordering::SeqCst);
+        
+        let mut out = stdout();
+        if let Err(error) = out.write_all(ENTER_SEQUENCE).and_then(|_| out.flush()) {
+            restore_terminal();
+            return Err(error);
+        }
+        Ok(())
+    }
+    
+    fn resize(&mut self, width: usize, height: usize, _font_width: i16, _font_height: i16) -> Result<(), Error> {
+        check_terminal_size(width, height)?;
+        
+        let mut out = stdout();
+        out.write_all(b"\x1b[0m\x1b[2J")?;
+        out.flush()
+    }
+    
+    fn present(&mut self, width: usize, height: usize, glyphs: &[char], colors: &[u16]) -> Result<(), Error> {
+        self.output.clear();
+        let mut last_col = None;
+        for y in 0..height {
+            self.output.extend_from_slice(format!("\x1b[{};1H", y + 1).as_bytes());
+            for x in 0..width {
+                let col = colors[y * width + x];
+                if last_col != Some(col) {
+                    self.output.extend_from_slice(sgr(col).as_bytes());
+                    last_col = Some(col);
+                }
+                let c = match glyphs[y * width + x] {
+                    c if c < ' ' || c == '\u{7f}' => ' ',
+                    c => c
+                };
+                let mut buffer = [0; 4];
+                self.output.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            }
+        }
+        
+        let mut out = stdout();
+        out.write_all(&self.output)?;
+        out.flush()
+    }
+    
+    fn poll_keys(&mut self, key_states: &mut [bool; 256]) -> Result<(), Error> {
+        let now = Instant::now();
+        for v_key in self.read_input() {
+            if self.key_last_seen[v_key].is_some() {
+                self.key_repeating[v_key] = true;
+            }
+            self.key_last_seen[v_key] = Some(now);
+        }
+        
+        for (v_key, key_state) in key_states.iter_mut().enumerate() {
+            let delay = if self.key_repeating[v_key] { KEY_REPEAT_DELAY } else { KEY_HOLD_DELAY };
+            *key_state = match self.key_last_seen[v_key] {
+                Some(last_seen) => now.duration_since(last_seen) < delay,
+                None => false
+            };
+            if !*key_state {
+                self.key_last_seen[v_key] = None;
+                self.key_repeating[v_key] = false;
+            }
+        }
+        Ok(())
+    }
+    
+    fn flush_input(&mut self) -> Result<(), Error> {
+        let ret = unsafe { tcflush(STDIN_FILENO, TCIFLUSH) };
+        if ret == -1 { return Err(Error::last_os_error()); }
+        Ok(())
+    }
+    
+    fn set_title(&mut self, title: &str) -> Result<(), Error> {
+        // control characters, ESC and BEL above all, would end the sequence and start another
+        let title: String = title.chars().filter(|c| !c.is_control()).collect();
+        let mut out = stdout();
+        write!(out, "\x1b]0;{}\x07", title)?;
+        out.flush()
+    }
+}
+
+impl Drop for TerminalBackend {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
@@ -0,0 +1,56 @@
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right
+}
+
+// width and height in cells of text once laid out, wrapped at wrap_width if there is one
+pub fn measure_text(text: &str, wrap_width: Option<usize>) -> (usize, usize) {
+    let lines = layout_text(text, wrap_width);
+    let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    (width, lines.len())
+}
+
+// splits text into lines on '\n', and on word boundaries when wrapping,
+// words longer than the wrap width are broken wherever they have to be
+pub(crate) fn layout_text(text: &str, wrap_width: Option<usize>) -> Vec<String> {
+    let mut lines = Vec::new();
+    if text.is_empty() || wrap_width == Some(0) {
+        return lines;
+    }
+    
+    for paragraph in text.split('\n') {
+        let paragraph = paragraph.strip_suffix('\r').unwrap_or(paragraph);
+        let width = match wrap_width {
+            Some(width) => width,
+            None => {
+                lines.push(paragraph.to_string());
+                continue;
+            }
+        };
+        
+        let mut line = String::new();
+        let mut line_length = 0;
+        for (i, word) in paragraph.split(' ').enumerate() {
+            let mut word: Vec<char> = word.chars().collect();
+            if i > 0 {
+                if line_length + 1 + word.len() <= width {
+                    line.push(' ');
+                    line.extend(word.iter());
+                    line_length += 1 + word.len();
+                    continue;
+                }
+                lines.push(line);
+                line = String::new();
+            }
+            while word.len() > width {
+                lines.push(word.drain(..width).collect());
+            }
+            line.extend(word.iter());
+            line_length = word.len();
+        }
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,585 @@
+use super::{RustConsole, RustConsoleSprite, Tile, TileMap, TiledError};
+use super::palette::nearest_color;
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+use serde_json::Value;
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+// the top bits of a global tile id flip and rotate the tile, tile maps don't do either so they are dropped
+const GID_FLAGS: u32 = 0xf000_0000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TiledProperty {
+    Bool(bool),
+    // int and object properties
+    Int(i64),
+    Float(f64),
+    // string, file and color properties, colors as "#AARRGGBB"
+    String(String)
+}
+
+impl TiledProperty {
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            TiledProperty::Bool(value) => Some(*value),
+            _ => None
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            TiledProperty::Int(value) => Some(*value),
+            _ => None
+        }
+    }
+
+    // ints too
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            TiledProperty::Float(value) => Some(*value),
+            TiledProperty::Int(value) => Some(*value as f64),
+            _ => None
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            TiledProperty::String(value) => Some(value),
+            _ => None
+        }
+    }
+
+    // the rgb of a "#AARRGGBB" or "#RRGGBB" color, the alpha is dropped
+    pub fn as_color(&self) -> Option<[u8; 3]> {
+        let hex = self.as_str()?.strip_prefix('#')?;
+        let rgb = match hex.len() {
+            8 => &hex[2..],
+            6 => hex,
+            _ => return None
+        };
+        if !rgb.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        let value = u32::from_str_radix(rgb, 16).ok()?;
+        Some([(value >> 16) as u8, (value >> 8) as u8, value as u8])
+    }
+}
+
+pub type TiledProperties = HashMap<String, TiledProperty>;
+
+// an object of an object layer, usually somewhere for the game to spawn something
+#[derive(Clone, Debug, PartialEq)]
+pub struct TiledObject {
+    pub id: u32,
+    pub name: String,
+    // the class of the object, its type in maps saved before tiled 1.9
+    pub class: String,
+    // the name of its object layer
+    pub layer: String,
+    // in tiles of the map, tiled's pixels divided by its tile size
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    // for tile objects, the id of the tile in the tile map
+    pub tile: Option<u32>,
+    pub properties: TiledProperties
+}
+
+pub struct TiledMap {
+    // tile ids are tiled's global tile ids
+    pub map: TileMap,
+    pub objects: Vec<TiledObject>,
+    pub properties: TiledProperties,
+    // by layer name, for tile and object layers
+    pub layer_properties: HashMap<String, TiledProperties>
+}
+
+// reads orthogonal tiled maps, .tmx or .tmj, into a TileMap. every tile becomes tile_width x
+// tile_height cells, taken from the sheet given for its tileset, where the tiles are laid out
+// in the same columns as in tiled, or from its glyph and color custom properties. tiles with a
+// true solid or water property get the matching flag
+pub struct TiledLoader {
+    tile_width: usize,
+    tile_height: usize,
+    sheets: HashMap<String, RustConsoleSprite>
+}
+
+struct RawMap {
+    width: usize,
+    height: usize,
+    // in pixels, for the objects
+    tile_width: f32,
+    tile_height: f32,
+    properties: TiledProperties,
+    tilesets: Vec<RawTileset>,
+    layers: Vec<RawLayer>
+}
+
+struct RawTileset {
+    first_gid: u32,
+    name: String,
+    tile_count: u32,
+    columns: u32,
+    tiles: HashMap<u32, TiledProperties>
+}
+
+enum RawLayer {
+    Tiles { name: String, visible: bool, properties: TiledProperties, gids: Vec<u32> },
+    Objects { name: String, properties: TiledProperties, objects: Vec<TiledObject> }
+}
+
+impl TiledLoader {
+    pub fn new(tile_width: usize, tile_height: usize) -> TiledLoader {
+        TiledLoader {
+            tile_width,
+            tile_height,
+            sheets: HashMap::new()
+        }
+    }
+
+    pub fn sheet(mut self, tileset: &str, sheet: RustConsoleSprite) -> TiledLoader {
+        self.sheets.insert(tileset.to_string(), sheet);
+        self
+    }
+
+    // .tmx as xml, .tmj and .json as json, external tilesets are read from next to the map
+    pub fn load(&self, path: &str) -> Result<TiledMap, TiledError> {
+        let path = Path::new(path);
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let text = fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("tmx") => self.parse_tmx(&text, dir),
+            Some("tmj") | Some("json") => self.parse_tmj(&text, dir),
+            _ => Err(TiledError::Unsupported(format!("map file {}", path.display())))
+        }
+    }
+
+    pub fn parse_tmj(&self, text: &str, dir: &Path) -> Result<TiledMap, TiledError> {
+        let json: Value = serde_json::from_str(text).map_err(|error| TiledError::Format(error.to_string()))?;
+        self.build(json_map(&json, dir)?)
+    }
+
+    pub fn parse_tmx(&self, text: &str, dir: &Path) -> Result<TiledMap, TiledError> {
+        let document = roxmltree::Document::parse(text).map_err(|error| TiledError::Format(error.to_string()))?;
+        self.build(xml_map(document.root_element(), dir)?)
+    }
+
+    fn build(&self, raw: RawMap) -> Result<TiledMap, TiledError> {
+        if raw.width.checked_mul(raw.height).is_none_or(|tiles| tiles > RustConsoleSprite::MAX_CELLS) {
+            return Err(TiledError::Format(format!("map of {}x{} tiles is too large", raw.width, raw.height)));
+        }
+        if !(raw.tile_width > 0f32 && raw.tile_height > 0f32) {
+            return Err(TiledError::Format(format!("invalid tile size {}x{}", raw.tile_width, raw.tile_height)));
+        }
+        let mut map = TileMap::new(raw.width, raw.height, self.tile_width, self.tile_height);
+        for tileset in raw.tilesets {
+            if tileset.first_gid.checked_add(tileset.tile_count).is_none_or(|end| end > GID_FLAGS) {
+                return Err(TiledError::Format(format!("tileset '{}' has too many tiles", tileset.name)));
+            }
+            // the tile count comes from the file, only the tiles with properties and the ones the sheet
+            // has cells for are defined, however many tiles the tileset claims
+            let mut ids: Vec<u32> = tileset.tiles.keys().copied().filter(|&id| id < tileset.tile_count).collect();
+            let sheet = match self.sheets.get(&tileset.name) {
+                Some(sheet) => {
+                    let columns = tileset.columns.max(1) as usize;
+                    let (sheet_columns, sheet_rows) = match (self.tile_width, self.tile_height) {
+                        (0, _) | (_, 0) => (0, 0),
+                        (tile_width, tile_height) => ((sheet.width() / tile_width).min(columns), sheet.height() / tile_height)
+                    };
+                    for row in 0..sheet_rows {
+                        for id in (0..sheet_columns).map(|column| row * columns + column) {
+                            if id < tileset.tile_count as usize {
+                                ids.push(id as u32);
+                            }
+                        }
+                    }
+                    Some(map.add_tileset(sheet.clone()))
+                },
+                // without a sheet every tile needs its own glyph
+                None if ids.len() < tileset.tile_count as usize => return Err(TiledError::MissingSheet(tileset.name)),
+                None => None
+            };
+            ids.sort_unstable();
+            ids.dedup();
+            let columns = tileset.columns.max(1);
+            for id in ids {
+                let properties = tileset.tiles.get(&id);
+                let property = |name: &str| properties.and_then(|properties| properties.get(name));
+                let glyph = property("glyph").and_then(|glyph| glyph.as_str()).and_then(|glyph| glyph.chars().next());
+                // an int color is a colour attribute like FG_RED | BG_BLUE, a color property the nearest foreground
+                let color = match property("color") {
+                    None => RustConsole::FG_WHITE,
+                    Some(color) => match color.as_int().and_then(|col| u16::try_from(col).ok()).or_else(|| color.as_color().map(nearest_color)) {
+                        Some(col) => col,
+                        None => return Err(TiledError::Format(format!("tile {} of tileset '{}' has an invalid color {:?}", id, tileset.name, color)))
+                    }
+                };
+                let mut tile = match (glyph, sheet) {
+                    (Some(c), _) => Tile::glyph(c, color),
+                    (None, Some(sheet)) => Tile::tileset(sheet, ((id % columns) as usize * self.tile_width) as i32, ((id / columns) as usize * self.tile_height) as i32),
+                    (None, None) => return Err(TiledError::MissingSheet(tileset.name))
+                };
+                if property("solid").and_then(|solid| solid.as_bool()) == Some(true) {
+                    tile = tile.with_flags(Tile::SOLID);
+                }
+                if property("water").and_then(|water| water.as_bool()) == Some(true) {
+                    tile = tile.with_flags(Tile::WATER);
+                }
+                map.define_tile(tileset.first_gid + id, tile);
+            }
+        }
+
+        let mut objects = Vec::new();
+        let mut layer_properties = HashMap::new();
+        for layer in raw.layers {
+            match layer {
+                RawLayer::Tiles { name, visible, properties, gids } => {
+                    if gids.len() != raw.width * raw.height {
+                        return Err(TiledError::Format(format!("layer '{}' has {} tiles instead of {}", name, gids.len(), raw.width * raw.height)));
+                    }
+                    let index = map.add_layer(&name);
+                    let layer = map.layer_mut(index).unwrap();
+                    layer.visible = visible;
+                    for (i, gid) in gids.into_iter().enumerate() {
+                        layer.set((i % raw.width) as i32, (i / raw.width) as i32, gid & !GID_FLAGS);
+                    }
+                    layer_properties.insert(name, properties);
+                },
+                RawLayer::Objects { name, properties, objects: layer_objects } => {
+                    for mut object in layer_objects {
+                        object.x /= raw.tile_width;
+                        object.y /= raw.tile_height;
+                        object.width /= raw.tile_width;
+                        object.height /= raw.tile_height;
+                        objects.push(object);
+                    }
+                    layer_properties.insert(name, properties);
+                }
+            }
+        }
+
+        Ok(TiledMap { map, objects, properties: raw.properties, layer_properties })
+    }
+}
+
+fn check_map(orientation: &str, infinite: bool) -> Result<(), TiledError> {
+    if orientation != "orthogonal" {
+        return Err(TiledError::Unsupported(format!("{} maps, only orthogonal maps can be loaded", orientation)));
+    }
+    if infinite {
+        return Err(TiledError::Unsupported("infinite maps, the map needs a fixed size".to_string()));
+    }
+    Ok(())
+}
+
+// layer data, either csv or base64 with optional zlib or gzip compression, compressed data is
+// inflated no further than the tile ids of the map, a tiny layer must not unpack into gigabytes
+fn decode_tiles(text: &str, encoding: &str, compression: &str, tiles: usize) -> Result<Vec<u32>, TiledError> {
+    match encoding {
+        "csv" => text.split(',').map(|gid| gid.trim()).filter(|gid| !gid.is_empty()).map(|gid| {
+            gid.parse::<u32>().map_err(|_| TiledError::Format(format!("invalid tile id '{}'", gid)))
+        }).collect(),
+        "base64" => {
+            let compressed = decode_base64(text)?;
+            let expected = tiles.min(RustConsoleSprite::MAX_CELLS) * 4;
+            let mut bytes = Vec::new();
+            match compression {
+                "" => bytes = compressed,
+                "zlib" => { ZlibDecoder::new(&compressed[..]).take(expected as u64 + 1).read_to_end(&mut bytes)?; },
+                "gzip" => { GzDecoder::new(&compressed[..]).take(expected as u64 + 1).read_to_end(&mut bytes)?; },
+                compression => return Err(TiledError::Unsupported(format!("{} compressed layers", compression)))
+            }
+            if !compression.is_empty() && bytes.len() > expected {
+                return Err(TiledError::Format("compressed layer data is larger than the map".to_string()));
+            }
+            if bytes.len() % 4 != 0 {
+                return Err(TiledError::Format("layer data is not a whole number of tile ids".to_string()));
+            }
+            Ok(bytes.chunks(4).map(|gid| u32::from_le_bytes([gid[0], gid[1], gid[2], gid[3]])).collect())
+        },
+        encoding => Err(TiledError::Unsupported(format!("{} encoded layers", encoding)))
+    }
+}
+
+fn decode_base64(text: &str) -> Result<Vec<u8>, TiledError> {
+    let mut bytes = Vec::new();
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in text.bytes().filter(|c| !c.is_ascii_whitespace()) {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            _ => return Err(TiledError::Format(format!("invalid base64 character '{}'", c as char)))
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Ok(bytes)
+}
+
+fn property(kind: &str, value: &str) -> Option<TiledProperty> {
+    match kind {
+        "bool" => Some(TiledProperty::Bool(value == "true")),
+        "int" | "object" => value.parse().ok().map(TiledProperty::Int),
+        "float" => value.parse().ok().map(TiledProperty::Float),
+        // class properties hold properties of their own, which have no flat value
+        "class" => None,
+        _ => Some(TiledProperty::String(value.to_string()))
+    }
+}
+
+fn missing(what: &str) -> TiledError {
+    TiledError::Format(format!("missing {}", what))
+}
+
+fn json_str<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or("")
+}
+
+fn json_u32(value: &Value, key: &str) -> Result<u32, TiledError> {
+    value.get(key).and_then(Value::as_u64).and_then(|number| u32::try_from(number).ok()).ok_or_else(|| missing(key))
+}
+
+fn json_f32(value: &Value, key: &str) -> f32 {
+    value.get(key).and_then(Value::as_f64).unwrap_or(0f64) as f32
+}
+
+fn json_properties(value: &Value) -> TiledProperties {
+    let mut properties = TiledProperties::new();
+    for entry in value.get("properties").and_then(Value::as_array).into_iter().flatten() {
+        let kind = entry.get("type").and_then(Value::as_str).unwrap_or("string");
+        let property = match entry.get("value") {
+            Some(Value::Bool(value)) => Some(TiledProperty::Bool(*value)),
+            Some(Value::Number(number)) if kind == "float" => number.as_f64().map(TiledProperty::Float),
+            Some(Value::Number(number)) => number.as_i64().map(TiledProperty::Int).or_else(|| number.as_f64().map(TiledProperty::Float)),
+            Some(Value::String(value)) => property(kind, value),
+            _ => None
+        };
+        if let Some(property) = property {
+            properties.insert(json_str(entry, "name").to_string(), property);
+        }
+    }
+    properties
+}
+
+fn json_map(json: &Value, dir: &Path) -> Result<RawMap, TiledError> {
+    check_map(json_str(json, "orientation"), json.get("infinite").and_then(Value::as_bool).unwrap_or(false))?;
+    let mut tilesets = Vec::new();
+    for tileset in json.get("tilesets").and_then(Value::as_array).into_iter().flatten() {
+        let first_gid = json_u32(tileset, "firstgid")?;
+        let source = json_str(tileset, "source");
+        tilesets.push(if source.is_empty() {
+            json_tileset(tileset, first_gid)?
+        } else {
+            external_tileset(&dir.join(source), first_gid)?
+        });
+    }
+    let (width, height) = (json_u32(json, "width")? as usize, json_u32(json, "height")? as usize);
+    let mut layers = Vec::new();
+    json_layers(json, true, width.saturating_mul(height), &mut layers)?;
+    Ok(RawMap {
+        width,
+        height,
+        tile_width: json_u32(json, "tilewidth")? as f32,
+        tile_height: json_u32(json, "tileheight")? as f32,
+        properties: json_properties(json),
+        tilesets,
+        layers
+    })
+}
+
+fn json_tileset(json: &Value, first_gid: u32) -> Result<RawTileset, TiledError> {
+    let mut tiles = HashMap::new();
+    for tile in json.get("tiles").and_then(Value::as_array).into_iter().flatten() {
+        tiles.insert(json_u32(tile, "id")?, json_properties(tile));
+    }
+    Ok(RawTileset {
+        first_gid,
+        name: json_str(json, "name").to_string(),
+        tile_count: json_u32(json, "tilecount")?,
+        columns: json_u32(json, "columns").unwrap_or(0),
+        tiles
+    })
+}
+
+// groups are flattened, their layers are only visible if the group is
+fn json_layers(json: &Value, visible: bool, tiles: usize, layers: &mut Vec<RawLayer>) -> Result<(), TiledError> {
+    for layer in json.get("layers").and_then(Value::as_array).into_iter().flatten() {
+        let name = json_str(layer, "name").to_string();
+        let visible = visible && layer.get("visible").and_then(Value::as_bool).unwrap_or(true);
+        match json_str(layer, "type") {
+            "tilelayer" => {
+                if layer.get("chunks").is_some() {
+                    return Err(TiledError::Unsupported("chunked layers of infinite maps".to_string()));
+                }
+                let gids = match layer.get("data") {
+                    Some(Value::Array(gids)) => gids.iter().map(|gid| {
+                        gid.as_u64().and_then(|gid| u32::try_from(gid).ok()).ok_or_else(|| TiledError::Format(format!("invalid tile id {}", gid)))
+                    }).collect::<Result<Vec<u32>, TiledError>>()?,
+                    Some(Value::String(data)) => decode_tiles(data, json_str(layer, "encoding"), json_str(layer, "compression"), tiles)?,
+                    _ => return Err(missing("layer data"))
+                };
+                layers.push(RawLayer::Tiles { name, visible, properties: json_properties(layer), gids });
+            },
+            "objectgroup" => {
+                let mut objects = Vec::new();
+                for object in layer.get("objects").and_then(Value::as_array).into_iter().flatten() {
+                    let class = if object.get("class").is_some() { json_str(object, "class") } else { json_str(object, "type") };
+                    objects.push(TiledObject {
+                        id: json_u32(object, "id").unwrap_or(0),
+                        name: json_str(object, "name").to_string(),
+                        class: class.to_string(),
+                        layer: name.clone(),
+                        x: json_f32(object, "x"),
+                        y: json_f32(object, "y"),
+                        width: json_f32(object, "width"),
+                        height: json_f32(object, "height"),
+                        tile: json_u32(object, "gid").ok().map(|gid| gid & !GID_FLAGS),
+                        properties: json_properties(object)
+                    });
+                }
+                layers.push(RawLayer::Objects { name, properties: json_properties(layer), objects });
+            },
+            "group" => json_layers(layer, visible, tiles, layers)?,
+            // image layers have nothing to draw on a console
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn external_tileset(path: &Path, first_gid: u32) -> Result<RawTileset, TiledError> {
+    let text = fs::read_to_string(path)?;
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("tsx") => {
+            let document = roxmltree::Document::parse(&text).map_err(|error| TiledError::Format(error.to_string()))?;
+            xml_tileset(document.root_element(), first_gid)
+        },
+        Some("tsj") | Some("json") => {
+            let json: Value = serde_json::from_str(&text).map_err(|error| TiledError::Format(error.to_string()))?;
+            json_tileset(&json, first_gid)
+        },
+        _ => Err(TiledError::Unsupported(format!("tileset file {}", path.display())))
+    }
+}
+
+fn xml_attribute<T: FromStr>(node: roxmltree::Node, name: &str) -> Result<T, TiledError> {
+    let value = node.attribute(name).ok_or_else(|| missing(name))?;
+    value.parse().map_err(|_| TiledError::Format(format!("invalid {} '{}'", name, value)))
+}
+
+fn xml_children<'a, 'input: 'a>(node: roxmltree::Node<'a, 'input>, name: &'a str) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> + 'a {
+    node.children().filter(move |child| child.has_tag_name(name))
+}
+
+fn xml_properties(node: roxmltree::Node) -> TiledProperties {
+    let mut properties = TiledProperties::new();
+    for entry in xml_children(node, "properties").flat_map(|properties| xml_children(properties, "property")) {
+        // multi-line strings are in the text of the element instead of its value
+        let value = entry.attribute("value").or_else(|| entry.text()).unwrap_or("");
+        if let Some(property) = property(entry.attribute("type").unwrap_or("string"), value) {
+            properties.insert(entry.attribute("name").unwrap_or("").to_string(), property);
+        }
+    }
+    properties
+}
+
+fn xml_map(node: roxmltree::Node, dir: &Path) -> Result<RawMap, TiledError> {
+    if !node.has_tag_name("map") {
+        return Err(missing("map element"));
+    }
+    check_map(node.attribute("orientation").unwrap_or(""), node.attribute("infinite") == Some("1"))?;
+    let mut tilesets = Vec::new();
+    for tileset in xml_children(node, "tileset") {
+        let first_gid = xml_attribute(tileset, "firstgid")?;
+        tilesets.push(match tileset.attribute("source") {
+            Some(source) => external_tileset(&dir.join(source), first_gid)?,
+            None => xml_tileset(tileset, first_gid)?
+        });
+    }
+    let (width, height): (usize, usize) = (xml_attribute(node, "width")?, xml_attribute(node, "height")?);
+    let mut layers = Vec::new();
+    xml_layers(node, true, width.saturating_mul(height), &mut layers)?;
+    Ok(RawMap {
+        width,
+        height,
+        tile_width: xml_attribute(node, "tilewidth")?,
+        tile_height: xml_attribute(node, "tileheight")?,
+        properties: xml_properties(node),
+        tilesets,
+        layers
+    })
+}
+
+fn xml_tileset(node: roxmltree::Node, first_gid: u32) -> Result<RawTileset, TiledError> {
+    let mut tiles = HashMap::new();
+    for tile in xml_children(node, "tile") {
+        tiles.insert(xml_attribute(tile, "id")?, xml_properties(tile));
+    }
+    Ok(RawTileset {
+        first_gid,
+        name: node.attribute("name").unwrap_or("").to_string(),
+        tile_count: xml_attribute(node, "tilecount")?,
+        columns: xml_attribute(node, "columns").unwrap_or(0),
+        tiles
+    })
+}
+
+fn xml_layers(node: roxmltree::Node, visible: bool, tiles: usize, layers: &mut Vec<RawLayer>) -> Result<(), TiledError> {
+    for layer in node.children().filter(|child| child.is_element()) {
+        let name = layer.attribute("name").unwrap_or("").to_string();
+        let visible = visible && layer.attribute("visible") != Some("0");
+        match layer.tag_name().name() {
+            "layer" => {
+                let data = xml_children(layer, "data").next().ok_or_else(|| missing("layer data"))?;
+                if xml_children(data, "chunk").next().is_some() {
+                    return Err(TiledError::Unsupported("chunked layers of infinite maps".to_string()));
+                }
+                let gids = match data.attribute("encoding") {
+                    // one tile element per tile, from before tiled had encodings
+                    None => xml_children(data, "tile").map(|tile| xml_attribute(tile, "gid").or(Ok(0))).collect::<Result<Vec<u32>, TiledError>>()?,
+                    Some(encoding) => decode_tiles(data.text().unwrap_or(""), encoding, data.attribute("compression").unwrap_or(""), tiles)?
+                };
+                layers.push(RawLayer::Tiles { name, visible, properties: xml_properties(layer), gids });
+            },
+            "objectgroup" => {
+                let mut objects = Vec::new();
+                for object in xml_children(layer, "object") {
+                    let class = object.attribute("class").or_else(|| object.attribute("type")).unwrap_or("");
+                    objects.push(TiledObject {
+                        id: xml_attribute(object, "id").unwrap_or(0),
+                        name: object.attribute("name").unwrap_or("").to_string(),
+                        class: class.to_string(),
+                        layer: name.clone(),
+                        x: xml_attribute(object, "x").unwrap_or(0f32),
+                        y: xml_attribute(object, "y").unwrap_or(0f32),
+                        width: xml_attribute(object, "width").unwrap_or(0f32),
+                        height: xml_attribute(object, "height").unwrap_or(0f32),
+                        tile: xml_attribute::<u32>(object, "gid").ok().map(|gid| gid & !GID_FLAGS),
+                        properties: xml_properties(object)
+                    });
+                }
+                layers.push(RawLayer::Objects { name, properties: xml_properties(layer), objects });
+            },
+            "group" => xml_layers(layer, visible, tiles, layers)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,202 @@
+use super::{BlitOptions, DrawTarget, Rect, RustConsoleSprite};
+
+use std::collections::HashMap;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TileGraphic {
+    // fills the whole tile
+    Glyph(char, u16),
+    // the tile sized block of cells of a tileset with its top left corner at x, y, ' ' is transparent
+    Tileset { tileset: usize, x: i32, y: i32 }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Tile {
+    pub graphic: TileGraphic,
+    pub flags: u32
+}
+
+impl Tile {
+    pub const SOLID: u32 = 0x0001;
+    pub const WATER: u32 = 0x0002;
+    // bits from here up are free for the game
+    pub const USER: u32 = 0x0100;
+
+    pub fn glyph(c: char, col: u16) -> Tile {
+        Tile { graphic: TileGraphic::Glyph(c, col), flags: 0 }
+    }
+
+    pub fn tileset(tileset: usize, x: i32, y: i32) -> Tile {
+        Tile { graphic: TileGraphic::Tileset { tileset, x, y }, flags: 0 }
+    }
+
+    pub fn with_flags(mut self, flags: u32) -> Tile {
+        self.flags |= flags;
+        self
+    }
+
+    pub fn has(&self, flags: u32) -> bool { self.flags & flags == flags }
+}
+
+pub struct TileLayer {
+    name: String,
+    width: usize,
+    height: usize,
+    // tile ids row by row, 0 is an empty tile
+    tiles: Vec<u32>,
+    pub visible: bool
+}
+
+impl TileLayer {
+    pub fn name(&self) -> &str { &self.name }
+
+    // 0 outside of the layer
+    pub fn get(&self, x: i32, y: i32) -> u32 {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return 0;
+        }
+        self.tiles[y as usize * self.width + x as usize]
+    }
+
+    // ignored outside of the layer
+    pub fn set(&mut self, x: i32, y: i32, id: u32) {
+        if x < 0 || y < 0 || x as usize >= self.width || y as usize >= self.height {
+            return;
+        }
+        self.tiles[y as usize * self.width + x as usize] = id;
+    }
+
+    pub fn fill(&mut self, id: u32) {
+        self.tiles.iter_mut().for_each(|tile| *tile = id);
+    }
+}
+
+// layers of tile ids drawn bottom to top, each id stands for a glyph / colour pair or for a
+// block of tile_width x tile_height cells of a tileset sprite
+pub struct TileMap {
+    width: usize,
+    height: usize,
+    tile_width: usize,
+    tile_height: usize,
+    tiles: HashMap<u32, Tile>,
+    tilesets: Vec<RustConsoleSprite>,
+    layers: Vec<TileLayer>
+}
+
+impl TileMap {
+    // width and height in tiles, tile_width and tile_height in console cells
+    pub fn new(width: usize, height: usize, tile_width: usize, tile_height: usize) -> TileMap {
+        TileMap {
+            width,
+            height,
+            tile_width,
+            tile_height,
+            tiles: HashMap::new(),
+            tilesets: Vec::new(),
+            layers: Vec::new()
+        }
+    }
+
+    pub fn width(&self) -> usize { self.width }
+
+    pub fn height(&self) -> usize { self.height }
+
+    pub fn tile_width(&self) -> usize { self.tile_width }
+
+    pub fn tile_height(&self) -> usize { self.tile_height }
+
+    // returns the index for TileGraphic::Tileset
+    pub fn add_tileset(&mut self, tileset: RustConsoleSprite) -> usize {
+        self.tilesets.push(tileset);
+        self.tilesets.len() - 1
+    }
+
+    pub fn tileset(&self, index: usize) -> Option<&RustConsoleSprite> { self.tilesets.get(index) }
+
+    // id 0 is always the empty tile, defining it does nothing
+    pub fn define_tile(&mut self, id: u32, tile: Tile) {
+        if id != 0 {
+            self.tiles.insert(id, tile);
+        }
+    }
+
+    pub fn tile(&self, id: u32) -> Option<&Tile> { self.tiles.get(&id) }
+
+    // a new empty layer on top of the others, returns its index
+    pub fn add_layer(&mut self, name: &str) -> usize {
+        self.layers.push(TileLayer {
+            name: name.to_string(),
+            width: self.width,
+            height: self.height,
+            tiles: vec![0; self.width * self.height],
+            visible: true
+        });
+        self.layers.len() - 1
+    }
+
+    pub fn layers(&self) -> &[TileLayer] { &self.layers }
+
+    pub fn layer(&self, index: usize) -> Option<&TileLayer> { self.layers.get(index) }
+
+    pub fn layer_mut(&mut self, index: usize) -> Option<&mut TileLayer> { self.layers.get_mut(index) }
+
+    pub fn find_layer(&self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.name == name)
+    }
+
+    // the flags of the tiles of every layer at x, y, hidden layers included, 0 outside of the map
+    pub fn flags_at(&self, x: i32, y: i32) -> u32 {
+        self.layers.iter().filter_map(|layer| self.tile(layer.get(x, y))).fold(0, |flags, tile| flags | tile.flags)
+    }
+}
+
+// drawing maps for everything that can be drawn on, kept out of DrawTarget so that the drawing
+// primitives don't depend on tile maps
+pub trait DrawTileMap: DrawTarget {
+    // camera_x, camera_y is the point of the map, in tiles, that ends up at 0, 0, fractions
+    // scroll by whole cells inside the tiles, and only the tiles that can be seen are drawn
+    fn draw_tilemap(&mut self, map: &TileMap, camera_x: f32, camera_y: f32) {
+        let (tw, th) = (map.tile_width() as i64, map.tile_height() as i64);
+        let clip = self.clip_rect();
+        if tw == 0 || th == 0 || clip.is_empty() || !camera_x.is_finite() || !camera_y.is_finite() {
+            return;
+        }
+        
+        // the map cell at 0, 0, then the tiles under the clip rect
+        let scroll_x = (camera_x as f64 * tw as f64).floor() as i64;
+        let scroll_y = (camera_y as f64 * th as f64).floor() as i64;
+        let (ox, oy) = self.origin();
+        let min_x = (clip.x as i64 - ox as i64).saturating_add(scroll_x);
+        let max_x = (clip.right() as i64 - ox as i64).saturating_add(scroll_x);
+        let min_y = (clip.y as i64 - oy as i64).saturating_add(scroll_y);
+        let max_y = (clip.bottom() as i64 - oy as i64).saturating_add(scroll_y);
+        let first_column = min_x.div_euclid(tw).max(0);
+        let last_column = ((max_x - 1).div_euclid(tw) + 1).min(map.width() as i64);
+        let first_row = min_y.div_euclid(th).max(0);
+        let last_row = ((max_y - 1).div_euclid(th) + 1).min(map.height() as i64);
+        
+        let options = BlitOptions::default();
+        for layer in map.layers().iter().filter(|layer| layer.visible) {
+            for ty in first_row..last_row {
+                for tx in first_column..last_column {
+                    let tile = match map.tile(layer.get(tx as i32, ty as i32)) {
+                        Some(tile) => tile,
+                        None => continue
+                    };
+                    let x = (tx * tw - scroll_x) as i32;
+                    let y = (ty * th - scroll_y) as i32;
+                    match tile.graphic {
+                        TileGraphic::Glyph(c, col) => self.fill(x, y, x + tw as i32, y + th as i32, c, col),
+                        TileGraphic::Tileset { tileset, x: sx, y: sy } => {
+                            if let Some(sheet) = map.tileset(tileset) {
+                                self.draw_partial_sprite(x, y, sheet, Rect::new(sx, sy, tw as i32, th as i32), &options);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T: DrawTarget + ?Sized> DrawTileMap for T {}
@@ -0,0 +1,88 @@
+// affine transform, maps (x, y) to (a * x + b * y + c, d * x + e * y + f)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform2D {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32
+}
+
+impl Transform2D {
+    pub fn identity() -> Transform2D {
+        Transform2D { a: 1f32, b: 0f32, c: 0f32, d: 0f32, e: 1f32, f: 0f32 }
+    }
+    
+    pub fn translation(x: f32, y: f32) -> Transform2D {
+        Transform2D { c: x, f: y, ..Transform2D::identity() }
+    }
+    
+    // angle in radians, clockwise on screen since y points down
+    pub fn rotation(angle: f32) -> Transform2D {
+        let (sin, cos) = angle.sin_cos();
+        Transform2D { a: cos, b: -sin, c: 0f32, d: sin, e: cos, f: 0f32 }
+    }
+    
+    pub fn scaling(x: f32, y: f32) -> Transform2D {
+        Transform2D { a: x, e: y, ..Transform2D::identity() }
+    }
+    
+    pub fn shearing(x: f32, y: f32) -> Transform2D {
+        Transform2D { b: x, d: y, ..Transform2D::identity() }
+    }
+    
+    // self first, then other
+    pub fn then(&self, other: &Transform2D) -> Transform2D {
+        Transform2D {
+            a: other.a * self.a + other.b * self.d,
+            b: other.a * self.b + other.b * self.e,
+            c: other.a * self.c + other.b * self.f + other.c,
+            d: other.d * self.a + other.e * self.d,
+            e: other.d * self.b + other.e * self.e,
+            f: other.d * self.c + other.e * self.f + other.f
+        }
+    }
+    
+    pub fn translate(&self, x: f32, y: f32) -> Transform2D {
+        self.then(&Transform2D::translation(x, y))
+    }
+    
+    pub fn rotate(&self, angle: f32) -> Transform2D {
+        self.then(&Transform2D::rotation(angle))
+    }
+    
+    pub fn scale(&self, x: f32, y: f32) -> Transform2D {
+        self.then(&Transform2D::scaling(x, y))
+    }
+    
+    pub fn shear(&self, x: f32, y: f32) -> Transform2D {
+        self.then(&Transform2D::shearing(x, y))
+    }
+    
+    // None if the transform squashes everything onto a line or a point
+    pub fn inverse(&self) -> Option<Transform2D> {
+        let det = self.a * self.e - self.b * self.d;
+        if det == 0f32 || !det.is_finite() {
+            return None;
+        }
+        Some(Transform2D {
+            a: self.e / det,
+            b: -self.b / det,
+            c: (self.b * self.f - self.e * self.c) / det,
+            d: -self.d / det,
+            e: self.a / det,
+            f: (self.d * self.c - self.a * self.f) / det
+        })
+    }
+    
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (self.a * x + self.b * y + self.c, self.d * x + self.e * y + self.f)
+    }
+}
+
+impl Default for Transform2D {
+    fn default() -> Transform2D {
+        Transform2D::identity()
+    }
+}
@@ -0,0 +1,195 @@
+use super::{RustConsoleSprite, SpriteError};
+use super::cp437::{char_to_cp437, cp437_to_char};
+use super::palette::{nearest_color, PALETTE};
+use super::sprite::read_up_to;
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+
+const VERSION: i32 = -1;
+// rexpaint itself stops at 9, this only keeps a broken header from looping for long
+const MAX_LAYERS: usize = 256;
+// u32 glyph, fg rgb, bg rgb
+const CELL_SIZE: usize = 10;
+// cells with this background are see-through in rexpaint
+const TRANSPARENT: [u8; 3] = [255, 0, 255];
+
+// REXPaint .xp files, a gzip stream of little endian i32 version and layer count, then for each
+// layer its i32 width and height and its cells column by column
+impl RustConsoleSprite {
+    // all the layers drawn into one sprite, cells that are transparent in every layer are ' '
+    pub fn from_xp(path: &str) -> Result<RustConsoleSprite, SpriteError> {
+        RustConsoleSprite::from_xp_reader(BufReader::new(File::open(path)?))
+    }
+
+    pub fn from_xp_reader(reader: impl Read) -> Result<RustConsoleSprite, SpriteError> {
+        let layers = read_layers(reader)?;
+        let (width, height) = (layers[0].width, layers[0].height);
+        let mut cells = vec![None; width * height];
+        for layer in &layers {
+            for (cell, &top) in cells.iter_mut().zip(layer.cells.iter()) {
+                if top.is_some() {
+                    *cell = top;
+                }
+            }
+        }
+        Ok(to_sprite(width, height, &cells))
+    }
+
+    // one sprite per layer, bottom first, transparent cells are ' ' so that draw_sprite stacks them
+    pub fn xp_layers(path: &str) -> Result<Vec<RustConsoleSprite>, SpriteError> {
+        RustConsoleSprite::xp_layers_from_reader(BufReader::new(File::open(path)?))
+    }
+
+    pub fn xp_layers_from_reader(reader: impl Read) -> Result<Vec<RustConsoleSprite>, SpriteError> {
+        Ok(read_layers(reader)?.iter().map(|layer| to_sprite(layer.width, layer.height, &layer.cells)).collect())
+    }
+
+    // a single opaque layer
+    pub fn to_xp(&self, path: &str) -> Result<(), SpriteError> {
+        RustConsoleSprite::layers_to_xp(std::slice::from_ref(self), path)
+    }
+
+    pub fn write_xp(&self, writer: impl Write) -> Result<(), SpriteError> {
+        RustConsoleSprite::write_xp_layers(std::slice::from_ref(self), writer)
+    }
+
+    // the first layer is opaque, ' ' cells of the layers above it are saved as transparent
+    pub fn layers_to_xp(layers: &[RustConsoleSprite], path: &str) -> Result<(), SpriteError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        RustConsoleSprite::write_xp_layers(layers, &mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn write_xp_layers(layers: &[RustConsoleSprite], writer: impl Write) -> Result<(), SpriteError> {
+        let first = match layers.first() {
+            Some(first) => first,
+            None => return Err(SpriteError::Format("an xp file needs at least one layer".to_string()))
+        };
+        if layers.len() > MAX_LAYERS {
+            return Err(SpriteError::Format(format!("an xp file can't have more than {} layers", MAX_LAYERS)));
+        }
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(layers.len() as i32).to_le_bytes());
+        for (i, layer) in layers.iter().enumerate() {
+            check_size(i, layer.width(), layer.height(), first.width(), first.height())?;
+            if layer.width() * layer.height() > RustConsoleSprite::MAX_CELLS || i32::try_from(layer.width()).is_err() || i32::try_from(layer.height()).is_err() {
+                return Err(SpriteError::TooLarge { width: layer.width(), height: layer.height() });
+            }
+            bytes.extend_from_slice(&(layer.width() as i32).to_le_bytes());
+            bytes.extend_from_slice(&(layer.height() as i32).to_le_bytes());
+            for x in 0..layer.width() {
+                for y in 0..layer.height() {
+                    let c = layer.get_glyph(x, y);
+                    if i > 0 && c == ' ' {
+                        bytes.extend_from_slice(&0u32.to_le_bytes());
+                        bytes.extend_from_slice(&[0, 0, 0]);
+                        bytes.extend_from_slice(&TRANSPARENT);
+                        continue;
+                    }
+                    let code = char_to_cp437(c).ok_or(SpriteError::InvalidGlyph { x, y, code: c as u32 })?;
+                    let col = layer.get_color(x, y);
+                    bytes.extend_from_slice(&(code as u32).to_le_bytes());
+                    bytes.extend_from_slice(&PALETTE[(col & 0xF) as usize]);
+                    bytes.extend_from_slice(&PALETTE[((col >> 4) & 0xF) as usize]);
+                }
+            }
+        }
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+        encoder.write_all(&bytes)?;
+        encoder.finish()?;
+        Ok(())
+    }
+}
+
+// a glyph and its colour attribute, None where the layer is transparent
+type Cell = Option<(char, u16)>;
+
+struct Layer {
+    width: usize,
+    height: usize,
+    // row by row, like the sprites
+    cells: Vec<Cell>
+}
+
+fn read_layers(reader: impl Read) -> Result<Vec<Layer>, SpriteError> {
+    let mut reader = GzDecoder::new(reader);
+    let mut offset = 0;
+    let _version = read_i32(&mut reader, &mut offset)?;
+    let count = read_i32(&mut reader, &mut offset)?;
+    if count <= 0 || count as usize > MAX_LAYERS {
+        return Err(SpriteError::Format(format!("invalid xp layer count {}", count)));
+    }
+
+    let mut layers: Vec<Layer> = Vec::new();
+    let mut total = 0usize;
+    for i in 0..count as usize {
+        let w = read_i32(&mut reader, &mut offset)?;
+        let h = read_i32(&mut reader, &mut offset)?;
+        if w <= 0 || h <= 0 {
+            return Err(SpriteError::Format(format!("invalid xp layer size {}x{}", w, h)));
+        }
+        let (width, height) = (w as usize, h as usize);
+        if let Some(first) = layers.first() {
+            check_size(i, width, height, first.width, first.height)?;
+        }
+        // every layer counts, a file can't get around the limit by repeating a layer
+        total = match width.checked_mul(height).and_then(|cells| cells.checked_add(total)) {
+            Some(total) if total <= RustConsoleSprite::MAX_CELLS => total,
+            _ => return Err(SpriteError::TooLarge { width, height })
+        };
+
+        let expected = width * height * CELL_SIZE;
+        let data = read_up_to(&mut reader, expected)?;
+        if data.len() < expected {
+            return Err(SpriteError::Truncated { expected: offset + expected, found: offset + data.len() });
+        }
+        offset += expected;
+
+        let mut cells = vec![None; width * height];
+        for (n, cell) in data.chunks_exact(CELL_SIZE).enumerate() {
+            let (x, y) = (n / height, n % height);
+            let bg = [cell[7], cell[8], cell[9]];
+            if bg == TRANSPARENT {
+                continue;
+            }
+            let code = u32::from_le_bytes([cell[0], cell[1], cell[2], cell[3]]);
+            let c = u8::try_from(code).map(cp437_to_char).map_err(|_| SpriteError::InvalidGlyph { x, y, code })?;
+            let col = nearest_color([cell[4], cell[5], cell[6]]) | nearest_color(bg) << 4;
+            cells[y * width + x] = Some((c, col));
+        }
+        layers.push(Layer { width, height, cells });
+    }
+    if reader.read(&mut [0u8])? > 0 {
+        return Err(SpriteError::TrailingBytes);
+    }
+    Ok(layers)
+}
+
+fn to_sprite(width: usize, height: usize, cells: &[Cell]) -> RustConsoleSprite {
+    let glyphs = cells.iter().map(|cell| cell.map_or(' ', |(c, _)| c)).collect();
+    let colors = cells.iter().map(|cell| cell.map_or(0, |(_, col)| col)).collect();
+    RustConsoleSprite::from_cells(width, height, glyphs, colors)
+}
+
+// rexpaint only makes layers the size of the whole image
+fn check_size(layer: usize, width: usize, height: usize, first_width: usize, first_height: usize) -> Result<(), SpriteError> {
+    if (width, height) != (first_width, first_height) {
+        return Err(SpriteError::Format(format!("xp layer {} is {}x{} but the first layer is {}x{}", layer, width, height, first_width, first_height)));
+    }
+    Ok(())
+}
+
+fn read_i32(reader: &mut impl Read, offset: &mut usize) -> Result<i32, SpriteError> {
+    let data = read_up_to(reader, 4)?;
+    if data.len() < 4 {
+        return Err(SpriteError::Truncated { expected: *offset + 4, found: *offset + data.len() });
+    }
+    *offset += 4;
+    Ok(i32::from_le_bytes([data[0], data[1], data[2], data[3]]))
+}
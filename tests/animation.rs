@@ -0,0 +1,148 @@
+use rust_console_game_engine::{AnimatedSprite, Animation, AnimationError, BlitOptions, Clip, DrawTarget, Playback, Rect, RustConsole, RustConsoleSprite};
+
+use std::rc::Rc;
+
+// four 2x1 frames side by side, "aa", "bb", "cc" and "dd", with one column left over
+fn sheet() -> RustConsoleSprite {
+    let mut sheet = RustConsoleSprite::new(9, 1).unwrap();
+    sheet.draw_string(0, 0, "aabbccddx", RustConsole::FG_WHITE);
+    sheet
+}
+
+fn frames(sprite: &mut AnimatedSprite, steps: usize, elapsed_time: f32) -> Vec<usize> {
+    let mut frames = vec![sprite.frame().unwrap()];
+    for _ in 0..steps {
+        sprite.update(elapsed_time);
+        frames.push(sprite.frame().unwrap());
+    }
+    frames
+}
+
+fn animation(clips: Vec<(&str, Clip)>) -> Rc<Animation> {
+    let mut animation = Animation::from_grid(sheet(), 2, 1).unwrap();
+    for (name, clip) in clips {
+        animation.add_clip(name, clip).unwrap();
+    }
+    Rc::new(animation)
+}
+
+#[test]
+fn grids_are_cut_row_by_row() {
+    let mut square = RustConsoleSprite::new(5, 5).unwrap();
+    square.draw(0, 0, 'x', RustConsole::FG_WHITE);
+    let animation = Animation::from_grid(square, 2, 2).unwrap();
+    assert_eq!(animation.frames(), &[Rect::new(0, 0, 2, 2), Rect::new(2, 0, 2, 2), Rect::new(0, 2, 2, 2), Rect::new(2, 2, 2, 2)]);
+    assert_eq!(Animation::from_grid(sheet(), 0, 1).err(), Some(AnimationError::InvalidFrameSize { width: 0, height: 1 }));
+    let animation = Animation::from_rects(sheet(), vec![Rect::new(8, 0, 1, 1), Rect::new(0, 0, 4, 1)]);
+    assert_eq!(animation.frames().len(), 2);
+}
+
+#[test]
+fn clips_are_validated() {
+    let mut animation = Animation::from_grid(sheet(), 2, 1).unwrap();
+    assert_eq!(animation.add_clip("empty", Clip::new(Playback::Loop)), Err(AnimationError::EmptyClip("empty".to_string())));
+    assert_eq!(animation.add_clip("far", Clip::uniform(2..5, 0.1f32, Playback::Loop)), Err(AnimationError::FrameOutOfRange { clip: "far".to_string(), frame: 4 }));
+    assert_eq!(animation.add_clip("stuck", Clip::new(Playback::Loop).frame(0, 0.1f32).frame(1, 0f32)), Err(AnimationError::InvalidDuration { clip: "stuck".to_string(), duration: 0f32 }));
+    assert!(animation.add_clip("nan", Clip::uniform(0..1, f32::NAN, Playback::Loop)).is_err());
+    assert!(animation.clip("far").is_none());
+    animation.add_clip("walk", Clip::uniform(0..4, 0.1f32, Playback::Loop)).unwrap();
+    assert_eq!(animation.clip("walk").unwrap().frames.len(), 4);
+
+    let mut sprite = AnimatedSprite::new(Rc::new(animation));
+    assert_eq!(sprite.frame(), None);
+    assert_eq!(sprite.play("run"), Err(AnimationError::UnknownClip("run".to_string())));
+    assert_eq!(sprite.clip(), None);
+}
+
+#[test]
+fn loop_wraps_around() {
+    let mut sprite = AnimatedSprite::new(animation(vec![("walk", Clip::uniform(vec![1, 2, 3], 1f32, Playback::Loop))]));
+    sprite.play("walk").unwrap();
+    assert_eq!(frames(&mut sprite, 7, 1f32), vec![1, 2, 3, 1, 2, 3, 1, 2]);
+    assert!(!sprite.is_finished());
+}
+
+#[test]
+fn ping_pong_bounces_without_repeating_the_ends() {
+    let mut sprite = AnimatedSprite::new(animation(vec![("breathe", Clip::uniform(0..4, 0.5f32, Playback::PingPong))]));
+    sprite.play("breathe").unwrap();
+    assert_eq!(frames(&mut sprite, 9, 0.5f32), vec![0, 1, 2, 3, 2, 1, 0, 1, 2, 3]);
+
+    let mut single = AnimatedSprite::new(animation(vec![("still", Clip::uniform(vec![2], 0.5f32, Playback::PingPong))]));
+    single.play("still").unwrap();
+    assert_eq!(frames(&mut single, 3, 0.75f32), vec![2, 2, 2, 2]);
+}
+
+#[test]
+fn one_shot_stops_on_the_last_frame() {
+    let mut sprite = AnimatedSprite::new(animation(vec![("die", Clip::new(Playback::OneShot).frame(0, 0.1f32).frame(3, 0.3f32))]));
+    sprite.play("die").unwrap();
+    sprite.update(0.25f32);
+    assert_eq!(sprite.frame(), Some(3));
+    assert!(!sprite.is_finished());
+    sprite.update(0.25f32);
+    assert_eq!(sprite.frame(), Some(3));
+    assert!(sprite.is_finished());
+    sprite.update(10f32);
+    assert_eq!(sprite.frame(), Some(3));
+
+    sprite.restart();
+    assert_eq!(sprite.frame(), Some(0));
+    assert!(!sprite.is_finished());
+}
+
+#[test]
+fn per_frame_durations_and_long_pauses() {
+    let clip = Clip::new(Playback::Loop).frame(0, 0.1f32).frame(1, 0.5f32).frame(2, 0.25f32);
+    let mut sprite = AnimatedSprite::new(animation(vec![("idle", clip)]));
+    sprite.play("idle").unwrap();
+    assert_eq!(frames(&mut sprite, 4, 0.125f32), vec![0, 1, 1, 1, 1]);
+    sprite.update(0.125f32);
+    assert_eq!(sprite.frame(), Some(2));
+    // a whole number of cycles later it is back where it was
+    sprite.update(8.5f32 * 1000f32);
+    assert_eq!(sprite.frame(), Some(2));
+    sprite.update(0.25f32);
+    assert_eq!(sprite.frame(), Some(0));
+    // negative, infinite and NaN times are ignored
+    sprite.update(-1f32);
+    sprite.update(f32::NAN);
+    sprite.update(f32::INFINITY);
+    assert_eq!(sprite.frame(), Some(0));
+}
+
+#[test]
+fn playing_the_same_clip_carries_on() {
+    let mut sprite = AnimatedSprite::new(animation(vec![
+        ("walk", Clip::uniform(0..2, 1f32, Playback::Loop)),
+        ("jump", Clip::uniform(2..4, 1f32, Playback::Loop))
+    ]));
+    sprite.play("walk").unwrap();
+    sprite.update(1.5f32);
+    sprite.play("walk").unwrap();
+    assert_eq!(sprite.frame(), Some(1));
+    sprite.update(0.5f32);
+    assert_eq!(sprite.frame(), Some(0));
+    sprite.play("jump").unwrap();
+    assert_eq!((sprite.clip(), sprite.frame()), (Some("jump"), Some(2)));
+    sprite.update(0.5f32);
+    sprite.play("walk").unwrap();
+    assert_eq!(sprite.frame(), Some(0));
+}
+
+#[test]
+fn draws_the_current_frame() {
+    let animation = animation(vec![("walk", Clip::uniform(0..4, 1f32, Playback::Loop))]);
+    let mut first = AnimatedSprite::new(Rc::clone(&animation));
+    let mut second = AnimatedSprite::new(animation);
+    first.play("walk").unwrap();
+    second.play("walk").unwrap();
+    second.update(2f32);
+
+    let mut target = RustConsoleSprite::new(6, 2).unwrap();
+    first.draw(&mut target, 0, 0);
+    second.draw(&mut target, 3, 0);
+    second.draw_with(&mut target, 1, 1, &BlitOptions { flip_horizontal: true, ..BlitOptions::scaled(2f32) });
+    assert_eq!((0..6).map(|x| target.get_glyph(x, 0)).collect::<String>(), "aa cc ");
+    assert_eq!((0..6).map(|x| target.get_glyph(x, 1)).collect::<String>(), " cccc ");
+}
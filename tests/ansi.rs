@@ -0,0 +1,119 @@
+use rust_console_game_engine::{ColorKey, RustConsole, RustConsoleSprite, Sauce, SpriteError};
+
+fn asset(name: &str) -> String {
+    format!("{}/tests/assets/art/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn rows(sprite: &RustConsoleSprite) -> Vec<String> {
+    (0..sprite.height()).map(|y| (0..sprite.width()).map(|x| sprite.get_glyph(x, y)).collect()).collect()
+}
+
+fn ans(bytes: &[u8]) -> RustConsoleSprite {
+    RustConsoleSprite::from_ans_reader(bytes).unwrap()
+}
+
+// a SAUCE record for an ansi file of the given width
+fn sauce(width: u16, flags: u8) -> Vec<u8> {
+    let mut record = b"SAUCE00".to_vec();
+    record.extend_from_slice(&[b' '; 83]);
+    record.extend_from_slice(&[0, 0, 0, 0, 1, 1]);
+    record.extend_from_slice(&width.to_le_bytes());
+    record.extend_from_slice(&[0; 6]);
+    record.extend_from_slice(&[0, flags]);
+    record.extend_from_slice(&[0; 22]);
+    record
+}
+
+#[test]
+fn ans_files_use_their_sauce_record() {
+    let sprite = RustConsoleSprite::from_ans(&asset("box.ans")).unwrap();
+    assert_eq!(rows(&sprite), vec!["╔═╗", "║A║", "╚═╝"]);
+    assert_eq!(sprite.get_color(0, 0), RustConsole::FG_YELLOW | RustConsole::BG_DARK_BLUE);
+    assert_eq!(sprite.get_color(0, 1), RustConsole::FG_WHITE);
+    // bold carries on through a colour change
+    assert_eq!(sprite.get_color(1, 1), RustConsole::FG_RED);
+    assert_eq!(sprite.get_color(0, 2), RustConsole::FG_GREY);
+
+    let bytes = std::fs::read(asset("box.ans")).unwrap();
+    let sauce = Sauce::read(&bytes).unwrap();
+    assert_eq!((sauce.title.as_str(), sauce.author.as_str(), sauce.group.as_str(), sauce.date.as_str()), ("Box", "florin", "", "20240101"));
+    assert_eq!((sauce.data_type, sauce.file_type, sauce.width, sauce.height), (1, 1, 3, 3));
+    assert_eq!(sauce.comments, vec!["drawn for the tests".to_string()]);
+    assert!(!sauce.ice_colors);
+    assert_eq!(Sauce::read(b"no record"), None);
+}
+
+#[test]
+fn cursor_movement_places_the_glyphs() {
+    let mut bytes = b"ab\x1b[2Cc\x1b[3;2Hd\x1b[sx\x1b[Ae\x1b[u\x1b[Bf\x1b[2Dg\x1b[?7h".to_vec();
+    bytes.extend(sauce(6, 0));
+    assert_eq!(rows(&ans(&bytes)), vec!["ab  c ", "   e  ", " dx   ", " gf   "]);
+
+    // lines longer than the width wrap, without an extra row for the line break after them
+    let mut bytes = b"abcdefgh\r\nij\tk\x1b[1;4Hl\x1b[K".to_vec();
+    bytes.extend(sauce(4, 0));
+    assert_eq!(rows(&ans(&bytes)), vec!["abc ", "efgh", "ij k"]);
+    // without a record the art is 80 columns wide
+    assert_eq!(ans(b"x").width(), 80);
+    assert_eq!(ans(b"").height(), 0);
+}
+
+#[test]
+fn sgr_colours_map_onto_the_palette() {
+    let mut bytes = b"\x1b[7;32ma\x1b[27;5;41mb\x1b[mc\x1b[93;104md\x1b[38;5;196;48;2;0;0;120me\x1b[0;1;38;5;4mf\x1b[0;38;5;244mg\x1a\x1b[31mh".to_vec();
+    bytes.extend(sauce(7, 1));
+    let sprite = ans(&bytes);
+    assert_eq!(rows(&sprite), vec!["abcdefg"]);
+    let colors: Vec<u16> = (0..7).map(|x| sprite.get_color(x, 0)).collect();
+    assert_eq!(colors, vec![
+        RustConsole::BG_DARK_GREEN,
+        // turning reverse off keeps the colours, blink is a bright background with iCE colours
+        RustConsole::FG_DARK_GREEN | RustConsole::BG_RED,
+        RustConsole::FG_GREY,
+        RustConsole::FG_YELLOW | RustConsole::BG_BLUE,
+        RustConsole::FG_RED | RustConsole::BG_DARK_BLUE,
+        // bold doesn't change colours given by number
+        RustConsole::FG_DARK_BLUE,
+        RustConsole::FG_DARK_GREY
+    ]);
+    // without them blink does nothing
+    assert_eq!(ans(b"\x1b[5;41mb").get_color(0, 0), RustConsole::FG_GREY | RustConsole::BG_DARK_RED);
+}
+
+#[test]
+fn ans_errors() {
+    let far = b"\x1b[99999999;1Hx";
+    assert!(matches!(RustConsoleSprite::from_ans_reader(&far[..]), Err(SpriteError::TooLarge { width: 80, .. })));
+    assert!(matches!(RustConsoleSprite::from_ans(&asset("missing.ans")), Err(SpriteError::Io(_))));
+}
+
+#[test]
+fn text_files_are_coloured_by_their_key() {
+    let key = ColorKey::from_path(&asset("room.key")).unwrap();
+    let sprite = RustConsoleSprite::from_text(&asset("room.txt"), Some(&key)).unwrap();
+    assert_eq!(rows(&sprite), vec!["#####", "#.$.#", "#####"]);
+    assert_eq!(sprite.get_color(0, 0), RustConsole::FG_GREY | RustConsole::BG_DARK_BLUE);
+    assert_eq!(sprite.get_color(1, 1), RustConsole::FG_DARK_GREY);
+    assert_eq!(sprite.get_color(2, 1), RustConsole::FG_YELLOW);
+
+    // short lines are padded, and without a key everything but ' ' is white
+    let sprite = RustConsoleSprite::from_text_reader("\u{feff}ab\r\n\tc\n♥".as_bytes(), None).unwrap();
+    assert_eq!(rows(&sprite), vec!["ab       ", "        c", "♥        "]);
+    assert_eq!((sprite.get_color(0, 0), sprite.get_color(2, 0)), (RustConsole::FG_WHITE, RustConsole::FG_BLACK));
+    let key = ColorKey::new().set(' ', RustConsole::BG_DARK_BLUE);
+    let sprite = RustConsoleSprite::from_text_reader("a b".as_bytes(), Some(&key)).unwrap();
+    assert_eq!((sprite.get_color(0, 0), sprite.get_color(1, 0)), (RustConsole::FG_WHITE, RustConsole::BG_DARK_BLUE));
+}
+
+#[test]
+fn broken_keys_and_text_are_format_errors() {
+    match ColorKey::from_reader("# grey\n. pink".as_bytes()) {
+        Err(SpriteError::Format(message)) => assert_eq!(message, "line 2 of the colour key: unknown colour 'pink'"),
+        other => panic!("expected a format error, got {:?}", other.err())
+    }
+    assert!(matches!(ColorKey::from_reader("#grey".as_bytes()), Err(SpriteError::Format(_))));
+    assert!(matches!(ColorKey::from_reader("# 16".as_bytes()), Err(SpriteError::Format(_))));
+    assert!(matches!(ColorKey::from_reader("# red blue green".as_bytes()), Err(SpriteError::Format(_))));
+    assert_eq!(ColorKey::from_reader("  blue".as_bytes()).unwrap().get(' '), RustConsole::FG_BLUE);
+    assert!(matches!(RustConsoleSprite::from_text_reader(&[0xc9u8, 0xcd][..], None), Err(SpriteError::Format(_))));
+}
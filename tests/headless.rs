@@ -0,0 +1,232 @@
+use rust_console_game_engine::{DrawTarget, EngineError, HeadlessBackend, RustConsole, RustConsoleGame, RustConsoleGameEngine, TitlePolicy};
+
+use std::error::Error;
+
+struct Walker {
+    x: usize
+}
+
+impl RustConsoleGame for Walker {
+    fn name(&self) -> &str { "Walker" }
+
+    fn setup(&mut self) -> Result<(), Box<dyn Error>> {
+        self.x = 1;
+        Ok(())
+    }
+
+    fn update(&mut self, console: &mut RustConsole, _elapsed_time: f32) -> Result<(), Box<dyn Error>> {
+        if console.key(RustConsole::VK_RIGHT as usize).pressed {
+            self.x += 1;
+        }
+        console.clear();
+        console.draw(self.x as i32, 1, '@', RustConsole::FG_YELLOW | RustConsole::BG_DARK_BLUE);
+        Ok(())
+    }
+}
+
+#[test]
+fn scripted_keys_drive_update() {
+    let right = RustConsole::VK_RIGHT as usize;
+    let backend = HeadlessBackend::with_key_script(vec![vec![right], vec![right], vec![], vec![right]]);
+    let mut game = Walker { x: 0 };
+    let mut engine = RustConsoleGameEngine::with_backend(&mut game, Box::new(backend), 8, 3, 8, 8).unwrap();
+    engine.start().unwrap();
+    for _ in 0..5 {
+        engine.step(0.1f32).unwrap();
+    }
+
+    let console = engine.console();
+    assert_eq!(console.get_glyph(3, 1), '@');
+    assert_eq!(console.get_color(3, 1), RustConsole::FG_YELLOW | RustConsole::BG_DARK_BLUE);
+    assert_eq!(console.get_glyph(1, 1), ' ');
+
+    let sprite = console.to_sprite();
+    assert_eq!((sprite.width(), sprite.height()), (8, 3));
+    assert_eq!(sprite.get_glyph(3, 1), '@');
+}
+
+struct Quitter {
+    frames: usize,
+    destroyed: bool
+}
+
+impl RustConsoleGame for Quitter {
+    fn name(&self) -> &str { "Quitter" }
+
+    fn setup(&mut self) -> Result<(), Box<dyn Error>> { Ok(()) }
+
+    fn update(&mut self, console: &mut RustConsole, _elapsed_time: f32) -> Result<(), Box<dyn Error>> {
+        self.frames += 1;
+        if console.key(RustConsole::VK_ESCAPE as usize).pressed {
+            console.quit();
+        }
+        Ok(())
+    }
+
+    fn on_destroy(&mut self) -> Result<(), Box<dyn Error>> {
+        self.destroyed = true;
+        Ok(())
+    }
+}
+
+#[test]
+fn quit_ends_run_and_calls_on_destroy() {
+    let escape = RustConsole::VK_ESCAPE as usize;
+    let backend = HeadlessBackend::with_key_script(vec![vec![], vec![], vec![escape]]);
+    let mut game = Quitter { frames: 0, destroyed: false };
+    let mut engine = RustConsoleGameEngine::with_backend(&mut game, Box::new(backend), 8, 3, 8, 8).unwrap();
+    engine.run().unwrap();
+    drop(engine);
+
+    assert_eq!(game.frames, 3);
+    assert!(game.destroyed);
+}
+
+#[test]
+fn invalid_frame_rates_are_unlimited() {
+    let escape = RustConsole::VK_ESCAPE as usize;
+    for &fps in &[0f32, -30f32, f32::NAN, f32::INFINITY, 1e-40f32] {
+        let backend = HeadlessBackend::with_key_script(vec![vec![], vec![escape]]);
+        let mut game = Quitter { frames: 0, destroyed: false };
+        let mut engine = RustConsoleGameEngine::with_backend(&mut game, Box::new(backend), 8, 3, 8, 8).unwrap();
+        engine.set_target_fps(fps);
+        engine.run().unwrap();
+        drop(engine);
+        assert_eq!(game.frames, 2);
+    }
+}
+
+struct Failing {}
+
+impl RustConsoleGame for Failing {
+    fn name(&self) -> &str { "Failing" }
+
+    fn setup(&mut self) -> Result<(), Box<dyn Error>> { Ok(()) }
+
+    fn update(&mut self, _console: &mut RustConsole, _elapsed_time: f32) -> Result<(), Box<dyn Error>> {
+        Err("out of fuel".into())
+    }
+}
+
+#[test]
+fn update_errors_come_back_from_run() {
+    let mut game = Failing {};
+    let mut engine = RustConsoleGameEngine::with_backend(&mut game, Box::new(HeadlessBackend::new()), 8, 3, 8, 8).unwrap();
+    match engine.run() {
+        Err(EngineError::Game(error)) => assert_eq!(error.to_string(), "out of fuel"),
+        other => panic!("unexpected result: {:?}", other)
+    }
+}
+
+struct Ticker {
+    updates: Vec<f32>,
+    alphas: Vec<f32>
+}
+
+impl RustConsoleGame for Ticker {
+    fn name(&self) -> &str { "Ticker" }
+
+    fn setup(&mut self) -> Result<(), Box<dyn Error>> { Ok(()) }
+
+    fn update(&mut self, _console: &mut RustConsole, elapsed_time: f32) -> Result<(), Box<dyn Error>> {
+        self.updates.push(elapsed_time);
+        Ok(())
+    }
+
+    fn render(&mut self, _console: &mut RustConsole, alpha: f32) -> Result<(), Box<dyn Error>> {
+        self.alphas.push(alpha);
+        Ok(())
+    }
+}
+
+#[test]
+fn fixed_timestep_accumulates_and_interpolates() {
+    let mut game = Ticker { updates: Vec::new(), alphas: Vec::new() };
+    let mut engine = RustConsoleGameEngine::with_backend(&mut game, Box::new(HeadlessBackend::new()), 8, 3, 8, 8).unwrap();
+    engine.set_fixed_timestep(10f32, 3).unwrap();
+    engine.start().unwrap();
+    engine.step(0.25f32).unwrap();
+    engine.step(0.05f32).unwrap();
+    // a stall, only max_updates ticks are caught up with
+    engine.step(2f32).unwrap();
+    drop(engine);
+
+    assert_eq!(game.updates.len(), 6);
+    assert!(game.updates.iter().all(|&dt| (dt - 0.1f32).abs() < 1e-6));
+    assert_eq!(game.alphas.len(), 3);
+    assert!((game.alphas[0] - 0.5f32).abs() < 1e-4);
+    assert!(game.alphas[1].abs() < 1e-4);
+    assert!(game.alphas[2] < 1f32);
+}
+
+#[test]
+fn fixed_timestep_can_render_before_any_update() {
+    let mut game = Ticker { updates: Vec::new(), alphas: Vec::new() };
+    let mut engine = RustConsoleGameEngine::with_backend(&mut game, Box::new(HeadlessBackend::new()), 8, 3, 8, 8).unwrap();
+    engine.set_fixed_timestep(120f32, 8).unwrap();
+    engine.start().unwrap();
+    engine.step(0.0001f32).unwrap();
+    drop(engine);
+
+    assert!(game.updates.is_empty());
+    assert_eq!(game.alphas.len(), 1);
+}
+
+#[test]
+fn fixed_timestep_stops_ticking_once_quit() {
+    let mut game = Quitter { frames: 0, destroyed: false };
+    let backend = HeadlessBackend::with_key_script(vec![vec![RustConsole::VK_ESCAPE as usize]]);
+    let mut engine = RustConsoleGameEngine::with_backend(&mut game, Box::new(backend), 8, 3, 8, 8).unwrap();
+    engine.set_fixed_timestep(10f32, 8).unwrap();
+    engine.start().unwrap();
+    engine.step(0.5f32).unwrap();
+    assert!(engine.quit_requested());
+    drop(engine);
+
+    assert_eq!(game.frames, 1);
+}
+
+#[test]
+fn invalid_fixed_timesteps_are_rejected() {
+    let mut game = Ticker { updates: Vec::new(), alphas: Vec::new() };
+    let mut engine = RustConsoleGameEngine::with_backend(&mut game, Box::new(HeadlessBackend::new()), 8, 3, 8, 8).unwrap();
+    for &(tick_rate, max_updates) in &[(0f32, 8), (-60f32, 8), (f32::NAN, 8), (f32::INFINITY, 8), (60f32, 0)] {
+        assert!(matches!(engine.set_fixed_timestep(tick_rate, max_updates), Err(EngineError::InvalidTimestep { .. })));
+    }
+    // the engine keeps its variable timestep
+    engine.start().unwrap();
+    engine.step(0.25f32).unwrap();
+    drop(engine);
+
+    assert_eq!(game.updates, vec![0.25f32]);
+}
+
+#[test]
+fn frame_stats_cover_recent_frames() {
+    let mut game = Ticker { updates: Vec::new(), alphas: Vec::new() };
+    let mut engine = RustConsoleGameEngine::with_backend(&mut game, Box::new(HeadlessBackend::new()), 8, 3, 8, 8).unwrap();
+    engine.start().unwrap();
+    for i in 0..200 {
+        engine.step(if i % 100 == 0 { 0.1f32 } else { 0.01f32 }).unwrap();
+    }
+
+    let stats = engine.console().frame_stats();
+    assert!((stats.min - 0.01f32).abs() < 1e-6);
+    assert!((stats.max - 0.1f32).abs() < 1e-6);
+    assert!((stats.avg - 0.0109f32).abs() < 1e-5);
+    assert!((stats.low_1_percent - 0.1f32).abs() < 1e-6);
+    assert!((stats.fps() - 1f32 / 0.0109f32).abs() < 0.1f32);
+}
+
+#[test]
+fn overlay_title_policy_draws_fps_into_the_frame() {
+    let mut game = Ticker { updates: Vec::new(), alphas: Vec::new() };
+    let mut engine = RustConsoleGameEngine::with_backend(&mut game, Box::new(HeadlessBackend::new()), 16, 3, 8, 8).unwrap();
+    engine.set_title_policy(TitlePolicy::Overlay);
+    engine.start().unwrap();
+    engine.step(0.02f32).unwrap();
+
+    let console = engine.console();
+    let row = (0..16).map(|x| console.get_glyph(x, 0)).collect::<String>();
+    assert_eq!(row, "      FPS: 50.00");
+}
@@ -0,0 +1,98 @@
+use rust_console_game_engine::{nearest_color, ImageCells, ImageOptions, RustConsole, RustConsoleSprite, SpriteError, PALETTE};
+
+fn rgba(pixels: &[[u8; 4]]) -> Vec<u8> {
+    pixels.iter().flatten().copied().collect()
+}
+
+fn encode_png(width: u32, height: u32, color_type: png::ColorType, data: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut bytes, width, height);
+        encoder.set_color(color_type);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(data).unwrap();
+    }
+    bytes
+}
+
+#[test]
+fn palette_colours_become_solid_cells() {
+    let pixels: Vec<[u8; 4]> = PALETTE.iter().map(|c| [c[0], c[1], c[2], 255]).collect();
+    let sprite = RustConsoleSprite::from_rgba(16, 1, &rgba(&pixels), &ImageOptions::default()).unwrap();
+    for (i, color) in PALETTE.iter().enumerate() {
+        assert_eq!(sprite.get_glyph(i, 0), RustConsole::PIXEL_SOLID);
+        assert_eq!(sprite.get_color(i, 0), i as u16);
+        assert_eq!(nearest_color(*color), i as u16);
+    }
+}
+
+#[test]
+fn colours_between_the_palette_use_shade_glyphs() {
+    // half way between dark blue and black, a quarter of the way from dark blue to yellow, 3/4 of the way from dark green to white
+    let pixels = [[0, 0, 64, 255], [64, 64, 96, 255], [191, 223, 191, 255]];
+    let sprite = RustConsoleSprite::from_rgba(3, 1, &rgba(&pixels), &ImageOptions::default()).unwrap();
+    assert_eq!((sprite.get_glyph(0, 0), sprite.get_color(0, 0)), (RustConsole::PIXEL_HALF, RustConsole::FG_DARK_BLUE | RustConsole::BG_BLACK));
+    assert_eq!((sprite.get_glyph(1, 0), sprite.get_color(1, 0)), (RustConsole::PIXEL_QUARTER, RustConsole::FG_YELLOW | RustConsole::BG_DARK_BLUE));
+    assert_eq!((sprite.get_glyph(2, 0), sprite.get_color(2, 0)), (RustConsole::PIXEL_THREEQUARTER, RustConsole::FG_WHITE | RustConsole::BG_DARK_GREEN));
+}
+
+#[test]
+fn transparent_pixels_become_empty_cells() {
+    let pixels = [[255, 0, 0, 0], [255, 0, 0, 127], [255, 0, 0, 128]];
+    let sprite = RustConsoleSprite::from_rgba(3, 1, &rgba(&pixels), &ImageOptions::default()).unwrap();
+    assert_eq!(sprite.get_glyph(0, 0), ' ');
+    assert_eq!(sprite.get_glyph(1, 0), ' ');
+    assert_eq!((sprite.get_glyph(2, 0), sprite.get_color(2, 0)), (RustConsole::PIXEL_SOLID, RustConsole::FG_RED));
+}
+
+#[test]
+fn blocks_average_two_pixels_per_cell() {
+    let pixels = [[255, 0, 0, 255], [0, 0, 0, 255], [0, 255, 0, 255], [0, 255, 0, 0], [0, 0, 255, 255]];
+    let options = ImageOptions { cells: ImageCells::Block2x1, ..ImageOptions::default() };
+    let sprite = RustConsoleSprite::from_rgba(5, 1, &rgba(&pixels), &options).unwrap();
+    assert_eq!(sprite.width(), 3);
+    assert_eq!((sprite.get_glyph(0, 0), sprite.get_color(0, 0)), (RustConsole::PIXEL_HALF, RustConsole::FG_RED | RustConsole::BG_BLACK));
+    // the transparent pixel is left out of the average
+    assert_eq!((sprite.get_glyph(1, 0), sprite.get_color(1, 0)), (RustConsole::PIXEL_SOLID, RustConsole::FG_GREEN));
+    assert_eq!((sprite.get_glyph(2, 0), sprite.get_color(2, 0)), (RustConsole::PIXEL_SOLID, RustConsole::FG_BLUE));
+}
+
+#[test]
+fn dithering_spreads_the_error() {
+    // a flat orange the shades can't match exactly
+    let pixels = vec![[240, 150, 40, 255]; 64];
+    let plain = RustConsoleSprite::from_rgba(8, 8, &rgba(&pixels), &ImageOptions::default()).unwrap();
+    let dithered = RustConsoleSprite::from_rgba(8, 8, &rgba(&pixels), &ImageOptions { dither: true, ..ImageOptions::default() }).unwrap();
+    
+    let cells = |sprite: &RustConsoleSprite| {
+        let mut cells: Vec<(char, u16)> = (0..64).map(|i| (sprite.get_glyph(i % 8, i / 8), sprite.get_color(i % 8, i / 8))).collect();
+        cells.sort();
+        cells.dedup();
+        cells.len()
+    };
+    assert_eq!(cells(&plain), 1);
+    assert!(cells(&dithered) > 1);
+}
+
+#[test]
+fn reads_png_files() {
+    let rgb = encode_png(2, 1, png::ColorType::Rgb, &[255, 255, 0, 0, 0, 128]);
+    let sprite = RustConsoleSprite::from_png_reader(&rgb[..], &ImageOptions::default()).unwrap();
+    assert_eq!((sprite.width(), sprite.height()), (2, 1));
+    assert_eq!(sprite.get_color(0, 0), RustConsole::FG_YELLOW);
+    assert_eq!(sprite.get_color(1, 0), RustConsole::FG_DARK_BLUE);
+    
+    let gray = encode_png(1, 2, png::ColorType::GrayscaleAlpha, &[255, 255, 0, 0]);
+    let sprite = RustConsoleSprite::from_png_reader(&gray[..], &ImageOptions::default()).unwrap();
+    assert_eq!((sprite.get_glyph(0, 0), sprite.get_color(0, 0)), (RustConsole::PIXEL_SOLID, RustConsole::FG_WHITE));
+    assert_eq!(sprite.get_glyph(0, 1), ' ');
+}
+
+#[test]
+fn broken_pngs_are_errors() {
+    let png = encode_png(2, 2, png::ColorType::Rgba, &[0; 16]);
+    assert!(RustConsoleSprite::from_png_reader(&png[..png.len() / 2], &ImageOptions::default()).is_err());
+    assert!(matches!(RustConsoleSprite::from_png_reader(&b"not a png"[..], &ImageOptions::default()), Err(SpriteError::Format(_))));
+    assert!(matches!(RustConsoleSprite::from_rgba(2, 2, &[0; 15], &ImageOptions::default()), Err(SpriteError::Truncated { expected: 16, found: 15 })));
+}
@@ -0,0 +1,163 @@
+use rust_console_game_engine::{DrawTarget, HeadlessBackend, ImageOptions, RustConsole, RustConsoleGame, RustConsoleGameEngine, RustConsoleSprite, SpriteError, PALETTE};
+
+use std::error::Error;
+
+struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>
+}
+
+impl Image {
+    fn decode(bytes: &[u8]) -> Image {
+        let mut reader = png::Decoder::new(bytes).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!(info.color_type, png::ColorType::Rgb);
+        Image { width: info.width as usize, height: info.height as usize, pixels }
+    }
+
+    fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        let i = (y * self.width + x) * 3;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2]]
+    }
+
+    // the cell's pixels as '#' for its foreground and '.' for anything else, one row per line
+    fn cell(&self, x: usize, y: usize, font_width: usize, font_height: usize, fg: [u8; 3]) -> String {
+        let mut text = String::new();
+        for py in 0..font_height {
+            for px in 0..font_width {
+                text.push(if self.pixel(x * font_width + px, y * font_height + py) == fg { '#' } else { '.' });
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+fn export(sprite: &RustConsoleSprite, font_width: usize, font_height: usize) -> Image {
+    let mut bytes = Vec::new();
+    sprite.write_png(&mut bytes, font_width, font_height).unwrap();
+    Image::decode(&bytes)
+}
+
+#[test]
+fn cells_use_the_palette_at_the_font_size() {
+    let mut sprite = RustConsoleSprite::new(3, 2).unwrap();
+    sprite.draw(0, 0, RustConsole::PIXEL_SOLID, RustConsole::FG_RED);
+    sprite.draw(1, 0, ' ', RustConsole::FG_RED | RustConsole::BG_DARK_BLUE);
+    sprite.draw(2, 1, RustConsole::PIXEL_SOLID, RustConsole::FG_WHITE | RustConsole::BG_DARK_GREEN);
+    let image = export(&sprite, 4, 6);
+    assert_eq!((image.width, image.height), (12, 12));
+    for &(x, y) in [(0, 0), (3, 5), (1, 2)].iter() {
+        assert_eq!(image.pixel(x, y), PALETTE[12]);
+        assert_eq!(image.pixel(4 + x, y), PALETTE[1]);
+        assert_eq!(image.pixel(8 + x, 6 + y), PALETTE[15]);
+        assert_eq!(image.pixel(x, 6 + y), PALETTE[0]);
+    }
+}
+
+#[test]
+fn block_elements_fill_their_part_of_the_cell() {
+    let mut sprite = RustConsoleSprite::new(4, 1).unwrap();
+    sprite.draw_string(0, 0, "▀▐▗▒", RustConsole::FG_WHITE);
+    let image = export(&sprite, 4, 4);
+    let white = PALETTE[15];
+    assert_eq!(image.cell(0, 0, 4, 4, white), "####\n####\n....\n....\n");
+    assert_eq!(image.cell(1, 0, 4, 4, white), "..##\n..##\n..##\n..##\n");
+    assert_eq!(image.cell(2, 0, 4, 4, white), "....\n....\n..##\n..##\n");
+    assert_eq!(image.cell(3, 0, 4, 4, white), "#.#.\n.#.#\n#.#.\n.#.#\n");
+}
+
+#[test]
+fn box_drawing_joins_across_cells() {
+    let mut sprite = RustConsoleSprite::new(3, 2).unwrap();
+    sprite.draw_string(0, 0, "┌─╖\n╘═╝", RustConsole::FG_WHITE);
+    let image = export(&sprite, 6, 6);
+    let white = PALETTE[15];
+    assert_eq!(image.cell(0, 0, 6, 6, white), "......\n......\n......\n...###\n...#..\n...#..\n");
+    assert_eq!(image.cell(1, 0, 6, 6, white), "......\n......\n......\n######\n......\n......\n");
+    assert_eq!(image.cell(2, 0, 6, 6, white), "......\n......\n......\n#####.\n..#.#.\n..#.#.\n");
+    assert_eq!(image.cell(0, 1, 6, 6, white), "...#..\n...#..\n...###\n...#..\n...###\n......\n");
+    assert_eq!(image.cell(1, 1, 6, 6, white), "......\n......\n######\n......\n######\n......\n");
+    assert_eq!(image.cell(2, 1, 6, 6, white), "..#.#.\n..#.#.\n###.#.\n....#.\n#####.\n......\n");
+}
+
+#[test]
+fn ascii_uses_the_built_in_font() {
+    let mut sprite = RustConsoleSprite::new(2, 1).unwrap();
+    sprite.draw_string(0, 0, "Aé", RustConsole::FG_YELLOW | RustConsole::BG_DARK_RED);
+    let image = export(&sprite, 5, 8);
+    let yellow = PALETTE[14];
+    assert_eq!(image.cell(0, 0, 5, 8, yellow), ".....\n.##..\n#..#.\n#..#.\n####.\n#..#.\n#..#.\n.....\n");
+    // glyphs the font doesn't have are drawn as '?'
+    let mut question = RustConsoleSprite::new(1, 1).unwrap();
+    question.draw(0, 0, '?', RustConsole::FG_YELLOW | RustConsole::BG_DARK_RED);
+    assert_eq!(image.cell(1, 0, 5, 8, yellow), export(&question, 5, 8).cell(0, 0, 5, 8, yellow));
+    assert_eq!(image.pixel(5, 0), PALETTE[4]);
+}
+
+#[test]
+fn empty_sprites_and_fonts_are_rejected() {
+    let sprite = RustConsoleSprite::new(2, 2).unwrap();
+    assert!(matches!(sprite.write_png(Vec::new(), 0, 8), Err(SpriteError::Format(_))));
+    let empty = RustConsoleSprite::new(0, 3).unwrap();
+    assert!(matches!(empty.write_png(Vec::new(), 8, 8), Err(SpriteError::Format(_))));
+    assert!(matches!(sprite.write_png(Vec::new(), usize::MAX, 8), Err(SpriteError::TooLarge { width: 2, height: 2 })));
+}
+
+#[test]
+fn images_over_the_pixel_limit_are_rejected() {
+    // well under MAX_CELLS, but 16384x16384 pixels with that font
+    let sprite = RustConsoleSprite::new(1024, 1024).unwrap();
+    assert!(matches!(sprite.write_png(Vec::new(), 16, 16), Err(SpriteError::TooLarge { width: 1024, height: 1024 })));
+}
+
+#[test]
+fn exported_sprites_import_back() {
+    let mut sprite = RustConsoleSprite::new(3, 1).unwrap();
+    sprite.draw(0, 0, RustConsole::PIXEL_SOLID, RustConsole::FG_CYAN);
+    sprite.draw(1, 0, RustConsole::PIXEL_SOLID, RustConsole::FG_DARK_MAGENTA);
+    sprite.draw(2, 0, ' ', RustConsole::BG_GREY);
+    let path = std::env::temp_dir().join(format!("rcge-png-export-{}.png", std::process::id()));
+    let path = path.to_str().unwrap();
+    sprite.save_png(path, 1, 1).unwrap();
+    let imported = RustConsoleSprite::from_image(path, &ImageOptions::default()).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(imported.get_color(0, 0), RustConsole::FG_CYAN);
+    assert_eq!(imported.get_color(1, 0), RustConsole::FG_DARK_MAGENTA);
+    assert_eq!(imported.get_color(2, 0), RustConsole::FG_GREY);
+}
+
+struct Screenshot {
+    png: Vec<u8>
+}
+
+impl RustConsoleGame for Screenshot {
+    fn name(&self) -> &str { "Screenshot" }
+
+    fn setup(&mut self) -> Result<(), Box<dyn Error>> { Ok(()) }
+
+    fn update(&mut self, console: &mut RustConsole, _elapsed_time: f32) -> Result<(), Box<dyn Error>> {
+        console.clear();
+        console.draw(1, 0, RustConsole::PIXEL_SOLID, RustConsole::FG_GREEN);
+        self.png.clear();
+        console.write_png(&mut self.png)?;
+        Ok(())
+    }
+}
+
+#[test]
+fn console_frames_export_at_the_console_font_size() {
+    let mut game = Screenshot { png: Vec::new() };
+    {
+        let mut engine = RustConsoleGameEngine::with_backend(&mut game, Box::new(HeadlessBackend::new()), 4, 2, 6, 12).unwrap();
+        engine.start().unwrap();
+        engine.step(0.1f32).unwrap();
+    }
+    let image = Image::decode(&game.png);
+    assert_eq!((image.width, image.height), (24, 24));
+    assert_eq!(image.pixel(6, 0), PALETTE[10]);
+    assert_eq!(image.pixel(11, 11), PALETTE[10]);
+    assert_eq!(image.pixel(5, 0), PALETTE[0]);
+}
@@ -0,0 +1,296 @@
+use rust_console_game_engine::{assert_snapshot, run_frames, BlitOptions, DrawTarget, DrawTileMap, Rect, RustConsole, RustConsoleGame, RustConsoleSprite, TextAlign, Tile, TileMap, Transform2D, Transparency};
+
+use std::error::Error;
+use std::f32::consts::PI;
+
+struct Drawing {
+    draw: fn(&mut RustConsole)
+}
+
+impl RustConsoleGame for Drawing {
+    fn name(&self) -> &str { "Drawing" }
+
+    fn setup(&mut self) -> Result<(), Box<dyn Error>> { Ok(()) }
+
+    fn update(&mut self, console: &mut RustConsole, _elapsed_time: f32) -> Result<(), Box<dyn Error>> {
+        console.clear();
+        (self.draw)(console);
+        Ok(())
+    }
+}
+
+fn check(name: &str, width: usize, height: usize, draw: fn(&mut RustConsole)) {
+    let mut game = Drawing { draw };
+    let frame = run_frames(&mut game, width, height, 1, 0.01f32, Vec::new());
+    assert_snapshot(&frame, format!("{}/tests/snapshots/{}.snap", env!("CARGO_MANIFEST_DIR"), name));
+}
+
+#[test]
+fn draw_line() {
+    check("draw_line", 24, 12, |console| {
+        console.draw_line(0, 0, 23, 5, '#', RustConsole::FG_WHITE);
+        console.draw_line(23, 0, 12, 11, '*', RustConsole::FG_RED);
+        console.draw_line(2, 11, 6, 1, '+', RustConsole::FG_GREEN);
+        console.draw_line(20, 8, 3, 10, '-', RustConsole::FG_CYAN | RustConsole::BG_DARK_BLUE);
+        console.draw_line(9, 9, 9, 9, 'o', RustConsole::FG_YELLOW);
+    });
+}
+
+#[test]
+fn draw_triangle() {
+    check("draw_triangle", 24, 12, |console| {
+        console.draw_triangle(1, 1, 22, 4, 7, 10, RustConsole::PIXEL_SOLID, RustConsole::FG_MAGENTA);
+    });
+}
+
+#[test]
+fn fill_triangle() {
+    check("fill_triangle", 24, 12, |console| {
+        console.fill_triangle(1, 1, 22, 4, 7, 10, RustConsole::PIXEL_HALF, RustConsole::FG_GREEN | RustConsole::BG_DARK_GREEN);
+        console.fill_triangle(14, 7, 22, 7, 18, 11, RustConsole::PIXEL_SOLID, RustConsole::FG_RED);
+    });
+}
+
+#[test]
+fn draw_circle() {
+    check("draw_circle", 24, 12, |console| {
+        console.draw_circle(11, 5, 5, 'O', RustConsole::FG_BLUE);
+        console.draw_circle(11, 5, 2, 'o', RustConsole::FG_DARK_CYAN);
+    });
+}
+
+#[test]
+fn fill_circle() {
+    check("fill_circle", 24, 12, |console| {
+        console.fill_circle(11, 5, 5, RustConsole::PIXEL_QUARTER, RustConsole::FG_YELLOW | RustConsole::BG_DARK_RED);
+        console.fill_circle(11, 5, 2, RustConsole::PIXEL_SOLID, RustConsole::FG_WHITE);
+    });
+}
+
+#[test]
+fn fill_and_strings() {
+    check("fill_and_strings", 24, 6, |console| {
+        console.fill(2, 1, 22, 5, '.', RustConsole::FG_DARK_GREY);
+        console.draw_string(3, 2, "Hello, World", RustConsole::FG_WHITE | RustConsole::BG_DARK_BLUE);
+        console.draw_string_alpha(3, 3, "a l p h a", RustConsole::FG_YELLOW);
+    });
+}
+
+#[test]
+fn clipping() {
+    check("clipping", 24, 12, |console| {
+        console.draw_line(-10, -4, 30, 16, '\\', RustConsole::FG_WHITE);
+        console.draw_line(-100, 6, 100, 6, '-', RustConsole::FG_DARK_GREY);
+        console.draw_line(-5, -5, -1, 20, 'x', RustConsole::FG_RED);
+        console.draw_circle(0, 0, 4, 'O', RustConsole::FG_BLUE);
+        console.fill_circle(23, 11, 3, RustConsole::PIXEL_SOLID, RustConsole::FG_YELLOW);
+        console.fill_triangle(-6, 7, 12, 11, 0, 20, RustConsole::PIXEL_HALF, RustConsole::FG_GREEN);
+        console.fill_triangle(18, -6, 40, 2, 20, 4, RustConsole::PIXEL_SOLID, RustConsole::FG_MAGENTA);
+        console.fill(-3, -3, 2, 2, '.', RustConsole::FG_CYAN);
+        console.draw(-1, 3, '!', RustConsole::FG_RED);
+        console.draw(24, 3, '!', RustConsole::FG_RED);
+    });
+}
+
+#[test]
+fn far_off_screen_geometry_does_not_panic() {
+    check("far_off_screen", 8, 4, |console| {
+        console.draw_line(i32::MIN, i32::MIN, i32::MAX, i32::MAX, '#', RustConsole::FG_WHITE);
+        console.draw_circle(i32::MIN, 2, 5, 'O', RustConsole::FG_WHITE);
+        console.fill_circle(4, i32::MAX, 1000, 'O', RustConsole::FG_WHITE);
+        console.fill_triangle(-50, -50, -40, -50, -45, -60, '#', RustConsole::FG_WHITE);
+        console.draw_sprite(-100, -100, &console.to_sprite());
+        console.push_viewport(2, 1);
+        console.draw_sprite_transformed(&console.to_sprite(), &Transform2D::translation(1e30f32, -1e30f32));
+        console.pop_viewport();
+        console.draw_string(i32::MAX, 0, "abc", RustConsole::FG_WHITE);
+        console.draw_string_alpha(0, i32::MAX, "a\nb", RustConsole::FG_WHITE);
+        console.draw_text(Rect::new(i32::MAX - 2, i32::MAX - 1, 5, 5), "wide\nrows\nhere", RustConsole::FG_WHITE, TextAlign::Center, false);
+        console.draw_text(Rect::new(i32::MIN, i32::MIN, 4, 2), "longer than the rect", RustConsole::FG_WHITE, TextAlign::Right, false);
+    });
+}
+
+// radii and vertices this far out used to overflow or take minutes, only the visible rows are visited now
+#[test]
+fn huge_geometry_is_clipped() {
+    let rows = |sprite: &RustConsoleSprite| -> Vec<String> { (0..4).map(|y| (0..8).map(|x| sprite.get_glyph(x, y)).collect()).collect() };
+    let mut target = RustConsoleSprite::new(8, 4).unwrap();
+    target.draw_circle(4, 2, 2_000_000_000, '#', RustConsole::FG_WHITE);
+    target.draw_circle(1_500_000_000, 2, 1_500_000_000, 'O', RustConsole::FG_WHITE);
+    assert_eq!(rows(&target), vec!["O       "; 4]);
+    target.fill_circle(4, 2, 1_200_000_000, '#', RustConsole::FG_WHITE);
+    assert_eq!(rows(&target), vec!["########"; 4]);
+
+    let mut target = RustConsoleSprite::new(8, 4).unwrap();
+    target.fill_triangle(-200_000_000, 0, 4, 3, 7, 1, '#', RustConsole::FG_WHITE);
+    assert_eq!(rows(&target), vec!["        ", "########", "####### ", "#####   "]);
+    target.fill_triangle(i32::MIN, i32::MIN, i32::MAX, 0, 0, i32::MAX, '%', RustConsole::FG_WHITE);
+    assert_eq!(rows(&target), vec!["%%%%%%%%"; 4]);
+}
+
+#[test]
+fn clipped_strings() {
+    check("clipped_strings", 12, 4, |console| {
+        console.draw_string(8, 0, "runs past the edge", RustConsole::FG_WHITE);
+        console.draw_string(-3, 1, "starts before", RustConsole::FG_CYAN);
+        console.draw_string_alpha(7, 2, "two\nrows", RustConsole::FG_YELLOW);
+        console.draw_string(0, 3, "bottom\nis cut", RustConsole::FG_GREEN);
+    });
+}
+
+#[test]
+fn draw_text() {
+    check("draw_text", 16, 9, |console| {
+        console.fill(0, 0, 16, 9, '.', RustConsole::FG_DARK_GREY);
+        console.draw_text(Rect::new(1, 0, 14, 1), "Left", RustConsole::FG_WHITE, TextAlign::Left, false);
+        console.draw_text(Rect::new(1, 1, 14, 1), "Centre", RustConsole::FG_WHITE, TextAlign::Center, false);
+        console.draw_text(Rect::new(1, 2, 14, 1), "Right", RustConsole::FG_WHITE, TextAlign::Right, false);
+        console.draw_text(Rect::new(1, 3, 14, 1), "this line does not wrap", RustConsole::FG_RED, TextAlign::Left, false);
+        console.draw_text(Rect::new(2, 4, 10, 4), "wrapped inside a box\ncentred", RustConsole::FG_CYAN, TextAlign::Center, true);
+        console.draw_text(Rect::new(0, 8, 16, 1), "first\nsecond", RustConsole::FG_GREEN, TextAlign::Right, false);
+    });
+}
+
+#[test]
+fn clip_and_viewport() {
+    check("clip_and_viewport", 24, 10, |console| {
+        console.fill(0, 0, 24, 10, '.', RustConsole::FG_DARK_GREY);
+        
+        // a panel at (12, 2), everything inside is clipped to 10x6
+        console.push_viewport(12, 2);
+        console.push_clip(Rect::new(0, 0, 10, 6));
+        console.fill(-5, -5, 50, 50, ' ', RustConsole::BG_DARK_BLUE);
+        console.draw_line(-4, -4, 14, 14, '\\', RustConsole::FG_WHITE);
+        console.fill_circle(9, 5, 3, RustConsole::PIXEL_SOLID, RustConsole::FG_YELLOW);
+        console.draw_circle(0, 0, 3, 'o', RustConsole::FG_CYAN);
+        console.fill_triangle(-6, 6, 4, 2, 6, 12, RustConsole::PIXEL_HALF, RustConsole::FG_GREEN);
+        console.draw_string(2, 1, "clipped text", RustConsole::FG_RED);
+        
+        // nested clips only get smaller
+        console.push_viewport(1, 3);
+        console.push_clip(Rect::new(-10, 0, 30, 1));
+        console.draw_text(Rect::new(0, 0, 20, 2), "nested\nhidden", RustConsole::FG_MAGENTA, TextAlign::Left, false);
+        console.pop_clip();
+        console.pop_viewport();
+        console.pop_clip();
+        console.pop_viewport();
+        
+        console.draw_string(0, 9, "back to the screen", RustConsole::FG_WHITE);
+        console.pop_clip();
+        console.pop_viewport();
+    });
+}
+
+#[test]
+fn draw_into_sprite() {
+    check("draw_into_sprite", 20, 8, |console| {
+        let mut panel = RustConsoleSprite::new(10, 6).unwrap();
+        panel.fill(0, 0, 10, 6, RustConsole::PIXEL_QUARTER, RustConsole::FG_DARK_BLUE);
+        panel.draw_line(0, 0, 9, 5, '\\', RustConsole::FG_WHITE);
+        panel.fill_triangle(6, 0, 12, 0, 9, 4, RustConsole::PIXEL_SOLID, RustConsole::FG_RED);
+        panel.fill_circle(2, 5, 2, 'o', RustConsole::FG_GREEN);
+        panel.draw_string(-2, 2, "HUD panel", RustConsole::FG_YELLOW);
+        
+        console.draw_sprite(1, 1, &panel);
+        panel.clear();
+        panel.draw_text(Rect::new(0, 0, 10, 6), "cleared and reused", RustConsole::FG_CYAN, TextAlign::Center, true);
+        console.draw_sprite(11, 1, &panel);
+    });
+}
+
+// two 3x2 frames side by side, the second one is framed by cells in the key colour
+fn sheet() -> RustConsoleSprite {
+    let mut sheet = RustConsoleSprite::new(6, 2).unwrap();
+    sheet.draw_string(0, 0, "ab ", RustConsole::FG_WHITE);
+    sheet.draw_string(0, 1, "cde", RustConsole::FG_YELLOW);
+    sheet.draw_string(3, 0, "#X#", RustConsole::FG_MAGENTA);
+    sheet.draw_string(3, 1, "#Y#", RustConsole::FG_MAGENTA);
+    sheet.draw(4, 0, 'X', RustConsole::FG_CYAN);
+    sheet.draw(4, 1, 'Y', RustConsole::FG_CYAN);
+    sheet
+}
+
+#[test]
+fn draw_partial_sprite() {
+    check("draw_partial_sprite", 24, 10, |console| {
+        console.fill(0, 0, 24, 10, '.', RustConsole::FG_DARK_GREY);
+        let sheet = sheet();
+        let first = Rect::new(0, 0, 3, 2);
+        let second = Rect::new(3, 0, 3, 2);
+        
+        console.draw_partial_sprite(0, 0, &sheet, first, &BlitOptions::default());
+        console.draw_partial_sprite(4, 0, &sheet, first, &BlitOptions { flip_horizontal: true, ..BlitOptions::default() });
+        console.draw_partial_sprite(8, 0, &sheet, first, &BlitOptions { flip_vertical: true, transparency: Transparency::None, ..BlitOptions::default() });
+        console.draw_partial_sprite(12, 0, &sheet, second, &BlitOptions { transparency: Transparency::Color(RustConsole::FG_MAGENTA), ..BlitOptions::default() });
+        let mask = vec![true, false, true, false, false, false, false, true, false, false, false, false];
+        console.draw_partial_sprite(16, 0, &sheet, first, &BlitOptions { transparency: Transparency::Mask(mask), ..BlitOptions::default() });
+        
+        console.draw_partial_sprite(0, 3, &sheet, first, &BlitOptions::scaled(2f32));
+        console.draw_partial_sprite(7, 3, &sheet, second, &BlitOptions::scaled(1.5f32));
+        console.draw_partial_sprite(12, 3, &sheet, Rect::new(0, 0, 6, 2), &BlitOptions { scale_x: 2f32, scale_y: 3f32, flip_horizontal: true, ..BlitOptions::default() });
+        console.draw_partial_sprite(-1, 8, &sheet, Rect::new(2, 0, 6, 2), &BlitOptions::default());
+    });
+}
+
+#[test]
+fn draw_sprite_transformed() {
+    check("draw_sprite_transformed", 32, 12, |console| {
+        let mut arrow = RustConsoleSprite::new(5, 3).unwrap();
+        arrow.draw_string(0, 0, "  #  ", RustConsole::FG_RED);
+        arrow.draw_string(0, 1, "=====", RustConsole::FG_WHITE);
+        arrow.draw_string(0, 2, "  #  ", RustConsole::FG_RED);
+        
+        console.draw_sprite_transformed(&arrow, &Transform2D::translation(1f32, 1f32));
+        // about its centre
+        let centred = Transform2D::translation(-2.5f32, -1.5f32);
+        console.draw_sprite_transformed(&arrow, &centred.rotate(PI / 2f32).translate(10f32, 4f32));
+        console.draw_sprite_transformed(&arrow, &centred.rotate(PI / 4f32).scale(1.5f32, 1.5f32).translate(18f32, 5f32));
+        console.draw_sprite_transformed(&arrow, &centred.shear(1f32, 0f32).translate(26f32, 3f32));
+        console.draw_sprite_transformed(&arrow, &centred.scale(2f32, 2f32).rotate(PI).translate(5f32, 9f32));
+        console.draw_sprite_transformed(&arrow, &centred.rotate(-0.3f32).translate(31f32, 10f32));
+        console.draw_sprite_transformed(&arrow, &Transform2D::scaling(0f32, 1f32));
+    });
+}
+
+// 9x5 tiles of 3x2 cells, grass with water and walls, and trees from a tileset on top
+fn tilemap() -> TileMap {
+    let mut tileset = RustConsoleSprite::new(6, 2).unwrap();
+    tileset.draw_string(0, 0, " ^ /#\\", RustConsole::FG_GREEN);
+    tileset.draw_string(0, 1, " | |#|", RustConsole::FG_DARK_YELLOW);
+    let mut map = TileMap::new(9, 5, 3, 2);
+    let tileset = map.add_tileset(tileset);
+    map.define_tile(1, Tile::glyph('.', RustConsole::FG_DARK_GREEN));
+    map.define_tile(2, Tile::glyph('~', RustConsole::FG_CYAN | RustConsole::BG_DARK_BLUE).with_flags(Tile::WATER));
+    map.define_tile(3, Tile::glyph(RustConsole::PIXEL_SOLID, RustConsole::FG_GREY).with_flags(Tile::SOLID));
+    map.define_tile(4, Tile::tileset(tileset, 0, 0));
+    map.define_tile(5, Tile::tileset(tileset, 3, 0).with_flags(Tile::SOLID));
+    let ground = map.add_layer("ground");
+    let decor = map.add_layer("decor");
+    let rows = ["#########", "#..~~...#", "#..~~...#", "#.......#", "#########"];
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            map.layer_mut(ground).unwrap().set(x as i32, y as i32, match c { '#' => 3, '~' => 2, _ => 1 });
+        }
+    }
+    let decor = map.layer_mut(decor).unwrap();
+    decor.set(1, 1, 4);
+    decor.set(6, 2, 4);
+    decor.set(7, 3, 5);
+    map
+}
+
+#[test]
+fn draw_tilemap() {
+    check("draw_tilemap", 46, 12, |console| {
+        let map = tilemap();
+        console.draw_tilemap(&map, 0f32, 0f32);
+        // scrolled by a third of a tile and a half, inside a window
+        console.push_viewport(30, 1);
+        console.push_clip(Rect::new(0, 0, 15, 9));
+        console.draw_tilemap(&map, 4.34f32, 1.5f32);
+        console.pop_clip();
+        console.pop_viewport();
+        // nothing to draw past the end of the map
+        console.draw_tilemap(&map, 100f32, -100f32);
+    });
+}
@@ -0,0 +1,70 @@
+use rust_console_game_engine::{HeadlessBackend, RustConsole, RustConsoleGameEngine, Scene, SceneStack, SceneTransition};
+
+use std::cell::RefCell;
+use std::error::Error;
+use std::rc::Rc;
+
+type Log = Rc<RefCell<Vec<String>>>;
+
+struct Recorder {
+    name: &'static str,
+    log: Log,
+    transitions: Vec<SceneTransition>,
+    transparent: bool
+}
+
+impl Recorder {
+    fn new(name: &'static str, log: &Log, transitions: Vec<SceneTransition>, transparent: bool) -> Box<Recorder> {
+        Box::new(Recorder { name, log: log.clone(), transitions, transparent })
+    }
+
+    fn record(&self, event: &str) {
+        self.log.borrow_mut().push(format!("{} {}", self.name, event));
+    }
+}
+
+impl Scene for Recorder {
+    fn enter(&mut self) -> Result<(), Box<dyn Error>> { self.record("enter"); Ok(()) }
+
+    fn exit(&mut self) -> Result<(), Box<dyn Error>> { self.record("exit"); Ok(()) }
+
+    fn pause(&mut self) -> Result<(), Box<dyn Error>> { self.record("pause"); Ok(()) }
+
+    fn resume(&mut self) -> Result<(), Box<dyn Error>> { self.record("resume"); Ok(()) }
+
+    fn update(&mut self, _console: &mut RustConsole, _elapsed_time: f32) -> Result<SceneTransition, Box<dyn Error>> {
+        self.record("update");
+        Ok(if self.transitions.is_empty() { SceneTransition::None } else { self.transitions.remove(0) })
+    }
+
+    fn render(&mut self, _console: &mut RustConsole, _alpha: f32) -> Result<(), Box<dyn Error>> {
+        self.record("render");
+        Ok(())
+    }
+
+    fn transparent(&self) -> bool { self.transparent }
+}
+
+#[test]
+fn transitions_fire_hooks_in_order() {
+    let log = Log::default();
+    let pause = Recorder::new("pause", &log, vec![SceneTransition::Pop], true);
+    let race = Recorder::new("race", &log, vec![SceneTransition::Push(pause), SceneTransition::None, SceneTransition::Quit], false);
+    let title = Recorder::new("title", &log, vec![SceneTransition::Replace(race)], false);
+    let mut game = SceneStack::new("Scenes", title);
+
+    let mut engine = RustConsoleGameEngine::with_backend(&mut game, Box::new(HeadlessBackend::new()), 8, 3, 8, 8).unwrap();
+    engine.run().unwrap();
+    drop(engine);
+
+    assert!(game.is_empty());
+    assert_eq!(*log.borrow(), vec![
+        "title enter",
+        "title update", "title exit", "race enter", "race render",
+        "race update", "race pause", "pause enter", "race render", "pause render",
+        "pause update", "pause exit", "race resume", "race render",
+        "race update", "race render",
+        "race update", "race render",
+        "race exit"
+    ]);
+}
@@ -0,0 +1,110 @@
+use rust_console_game_engine::{RustConsoleSprite, SpriteError};
+
+const BLOCKS: &[u8] = include_bytes!("assets/blocks.spr");
+
+// small deterministic xorshift, good enough to shake out panics
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn header(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = width.to_le_bytes().to_vec();
+    bytes.extend_from_slice(&height.to_le_bytes());
+    bytes
+}
+
+// anything that loads has to write back exactly what was read
+fn check_round_trip(bytes: &[u8]) -> Result<(), SpriteError> {
+    let sprite = RustConsoleSprite::from_reader(bytes)?;
+    let mut written = Vec::new();
+    sprite.write_to(&mut written).unwrap();
+    assert_eq!(written, bytes);
+    Ok(())
+}
+
+#[test]
+fn every_truncation_is_reported() {
+    for len in 0..BLOCKS.len() {
+        match RustConsoleSprite::from_reader(&BLOCKS[..len]) {
+            Err(SpriteError::Truncated { expected, found }) => {
+                assert_eq!(found, len);
+                assert_eq!(expected, if len < 8 { 8 } else { BLOCKS.len() });
+            },
+            result => panic!("{} bytes gave {:?}", len, result.map(|_| ()))
+        }
+    }
+}
+
+#[test]
+fn trailing_bytes_are_rejected() {
+    let mut bytes = BLOCKS.to_vec();
+    bytes.push(0);
+    assert!(matches!(RustConsoleSprite::from_reader(&bytes[..]), Err(SpriteError::TrailingBytes)));
+}
+
+#[test]
+fn surrogate_glyphs_are_rejected() {
+    let mut bytes = header(2, 2);
+    bytes.extend_from_slice(&[0; 8]);
+    for glyph in &[0x41u16, 0x42, 0x43, 0xd834] {
+        bytes.extend_from_slice(&glyph.to_le_bytes());
+    }
+    match RustConsoleSprite::from_reader(&bytes[..]) {
+        Err(SpriteError::InvalidGlyph { x: 1, y: 1, code: 0xd834 }) => (),
+        result => panic!("unexpected result {:?}", result.map(|_| ()))
+    }
+}
+
+#[test]
+fn oversized_headers_are_rejected_without_allocating() {
+    for &(width, height) in &[(u32::MAX, u32::MAX), (u32::MAX, 1), (1 << 16, 1 << 16), (4097, 4096), (1 << 16, 257)] {
+        match RustConsoleSprite::from_reader(&header(width, height)[..]) {
+            Err(SpriteError::TooLarge { width: w, height: h }) => assert_eq!((w, h), (width as usize, height as usize)),
+            result => panic!("{}x{} gave {:?}", width, height, result.map(|_| ()))
+        }
+    }
+    // at the limit the header is accepted, but the data is missing
+    assert!(matches!(RustConsoleSprite::from_reader(&header(4096, 4096)[..]), Err(SpriteError::Truncated { found: 8, .. })));
+    // the limit is on cells, not on each side, so a long thin sprite is accepted too
+    assert!(matches!(RustConsoleSprite::from_reader(&header(1 << 16, 256)[..]), Err(SpriteError::Truncated { found: 8, .. })));
+}
+
+#[test]
+fn empty_sprites_are_valid() {
+    check_round_trip(&header(0, 0)).unwrap();
+    check_round_trip(&header(0, 100)).unwrap();
+}
+
+#[test]
+fn bit_flips_never_panic() {
+    for i in 0..BLOCKS.len() {
+        for bit in 0..8 {
+            let mut bytes = BLOCKS.to_vec();
+            bytes[i] ^= 1 << bit;
+            let _ = check_round_trip(&bytes);
+        }
+    }
+}
+
+#[test]
+fn random_inputs_never_panic() {
+    let mut rng = Rng(0x2545f4914f6cdd1d);
+    for _ in 0..5000 {
+        // mostly small headers, so that some inputs get past the size checks
+        let width = (rng.next() % 6) as u32;
+        let height = (rng.next() % 6) as u32;
+        let mut bytes = if rng.next().is_multiple_of(4) { Vec::new() } else { header(width, height) };
+        let len = rng.next() % (8 + 4 * 36);
+        for _ in 0..len {
+            bytes.push(rng.next() as u8);
+        }
+        let _ = check_round_trip(&bytes);
+    }
+}
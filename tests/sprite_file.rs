@@ -0,0 +1,58 @@
+use rust_console_game_engine::{RustConsoleSprite, SpriteError};
+
+use std::env;
+use std::fs;
+
+const BLOCKS: &[u8] = include_bytes!("assets/blocks.spr");
+
+#[test]
+fn reads_embedded_sprites() {
+    let sprite = RustConsoleSprite::from_reader(BLOCKS).unwrap();
+    assert_eq!((sprite.width(), sprite.height()), (4, 2));
+    assert_eq!(sprite.get_glyph(0, 0), '\u{2584}');
+    assert_eq!(sprite.get_glyph(2, 1), '\u{2557}');
+    assert_eq!(sprite.get_color(1, 0), 0x1e);
+    assert_eq!(sprite.get_color(0, 1), 0x70);
+}
+
+#[test]
+fn writes_back_identical_bytes() {
+    let sprite = RustConsoleSprite::from_reader(BLOCKS).unwrap();
+    let mut bytes = Vec::new();
+    sprite.write_to(&mut bytes).unwrap();
+    assert_eq!(bytes, BLOCKS);
+}
+
+#[test]
+fn saves_and_loads_files() {
+    let path = env::temp_dir().join(format!("rust-console-sprite-{}.spr", std::process::id()));
+    let path = path.to_str().unwrap();
+    
+    let mut sprite = RustConsoleSprite::new(3, 2).unwrap();
+    sprite.set_glyph(1, 0, 'x');
+    sprite.set_color(1, 0, 0x2a);
+    sprite.set_glyph(2, 1, '\u{2593}');
+    sprite.save(path).unwrap();
+    let loaded = RustConsoleSprite::from_path(path);
+    let bytes = fs::read(path);
+    fs::remove_file(path).unwrap();
+    
+    let loaded = loaded.unwrap();
+    assert_eq!(bytes.unwrap().len(), 8 + 4 * 3 * 2);
+    for y in 0..2 {
+        for x in 0..3 {
+            assert_eq!(loaded.get_glyph(x, y), sprite.get_glyph(x, y));
+            assert_eq!(loaded.get_color(x, y), sprite.get_color(x, y));
+        }
+    }
+}
+
+#[test]
+fn glyphs_outside_16_bits_cannot_be_written() {
+    let mut sprite = RustConsoleSprite::new(1, 1).unwrap();
+    sprite.set_glyph(0, 0, '\u{1f600}');
+    match sprite.write_to(Vec::new()) {
+        Err(SpriteError::InvalidGlyph { x: 0, y: 0, code: 0x1f600 }) => (),
+        result => panic!("unexpected result {:?}", result)
+    }
+}
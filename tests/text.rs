@@ -0,0 +1,27 @@
+use rust_console_game_engine::measure_text;
+
+#[test]
+fn measures_lines_without_wrapping() {
+    assert_eq!(measure_text("", None), (0, 0));
+    assert_eq!(measure_text("Score: 100", None), (10, 1));
+    assert_eq!(measure_text("Lives\nScore: 100\n", None), (10, 3));
+    assert_eq!(measure_text("a\r\nbc", None), (2, 2));
+}
+
+#[test]
+fn wraps_on_word_boundaries() {
+    assert_eq!(measure_text("the quick brown fox", Some(10)), (9, 2));
+    assert_eq!(measure_text("the quick brown fox", Some(19)), (19, 1));
+    assert_eq!(measure_text("one\ntwo three", Some(5)), (5, 3));
+}
+
+#[test]
+fn breaks_words_longer_than_the_wrap_width() {
+    assert_eq!(measure_text("abcdefghij", Some(4)), (4, 3));
+    assert_eq!(measure_text("ab abcdefgh", Some(4)), (4, 3));
+}
+
+#[test]
+fn nothing_fits_in_zero_width() {
+    assert_eq!(measure_text("text", Some(0)), (0, 0));
+}
@@ -0,0 +1,162 @@
+use rust_console_game_engine::{DrawTarget, DrawTileMap, RustConsole, RustConsoleSprite, Tile, TileGraphic, TiledError, TiledLoader, TiledMap, TiledProperty};
+
+use std::path::Path;
+
+fn asset(name: &str) -> String {
+    format!("{}/tests/assets/tiled/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+// the four terrain tiles as 2x1 cells, in the same two columns as the tileset image
+fn loader() -> TiledLoader {
+    let mut terrain = RustConsoleSprite::new(4, 2).unwrap();
+    terrain.draw_string(0, 0, "..##", RustConsole::FG_DARK_GREEN);
+    terrain.draw_string(0, 1, "~~,,", RustConsole::FG_BLUE);
+    TiledLoader::new(2, 1).sheet("terrain", terrain)
+}
+
+fn check_level(level: &TiledMap) {
+    let map = &level.map;
+    assert_eq!((map.width(), map.height(), map.tile_width(), map.tile_height()), (4, 3, 2, 1));
+    assert_eq!(map.layers().len(), 2);
+    let ground = map.layer(map.find_layer("ground").unwrap()).unwrap();
+    assert_eq!((0..4).map(|x| ground.get(x, 1)).collect::<Vec<u32>>(), vec![1, 3, 3, 2]);
+    assert!(ground.visible);
+    // hidden with its group, and without the flip bit of the second item
+    let items = map.layer(map.find_layer("items").unwrap()).unwrap();
+    assert!(!items.visible);
+    assert_eq!((items.get(2, 0), items.get(1, 1)), (5, 6));
+
+    assert_eq!(map.tile(1).unwrap().graphic, TileGraphic::Tileset { tileset: 0, x: 0, y: 0 });
+    assert_eq!(map.tile(4).unwrap().graphic, TileGraphic::Tileset { tileset: 0, x: 2, y: 1 });
+    assert_eq!(map.tile(5).unwrap().graphic, TileGraphic::Glyph('$', RustConsole::FG_YELLOW));
+    assert_eq!(*map.tile(6).unwrap(), Tile::glyph('!', RustConsole::FG_RED).with_flags(Tile::SOLID));
+    assert_eq!(map.flags_at(3, 0), Tile::SOLID);
+    // water on the ground, with a solid item above it
+    assert_eq!(map.flags_at(1, 1), Tile::WATER | Tile::SOLID);
+    assert_eq!(map.flags_at(0, 0), 0);
+
+    assert_eq!(level.properties["title"], TiledProperty::String("Cave".to_string()));
+    assert_eq!(level.properties["gravity"].as_float(), Some(9.5));
+    assert_eq!(level.properties["intro"].as_str(), Some("Mind\nthe gap"));
+    assert_eq!(level.layer_properties["ground"]["music"].as_str(), Some("cave.ogg"));
+    assert!(level.layer_properties["spawns"].is_empty());
+
+    let objects = &level.objects;
+    assert_eq!(objects.len(), 3);
+    assert_eq!((objects[0].name.as_str(), objects[0].x, objects[0].y), ("player", 1.5, 2.5));
+    assert_eq!((objects[1].class.as_str(), objects[1].width, objects[1].height), ("slime", 1.0, 0.5));
+    assert_eq!(objects[1].properties["hp"].as_int(), Some(3));
+    assert_eq!(objects[1].layer, "spawns");
+    assert_eq!((objects[2].id, objects[2].tile), (3, Some(5)));
+    assert_eq!(objects[0].tile, None);
+
+    let mut target = RustConsoleSprite::new(8, 3).unwrap();
+    target.draw_tilemap(map, 0f32, 0f32);
+    let rows: Vec<String> = (0..3).map(|y| (0..8).map(|x| target.get_glyph(x, y)).collect()).collect();
+    assert_eq!(rows, vec!["......##", "..~~~~##", ",,,,..##"]);
+}
+
+#[test]
+fn loads_tmx_maps() {
+    check_level(&loader().load(&asset("level.tmx")).unwrap());
+}
+
+#[test]
+fn loads_tmj_maps() {
+    check_level(&loader().load(&asset("level.tmj")).unwrap());
+}
+
+#[test]
+fn tilesets_need_a_sheet_or_glyphs() {
+    match TiledLoader::new(1, 1).load(&asset("level.tmx")) {
+        Err(TiledError::MissingSheet(tileset)) => assert_eq!(tileset, "terrain"),
+        other => panic!("expected a missing sheet, got {:?}", other.err())
+    }
+}
+
+fn unsupported(result: Result<TiledMap, TiledError>) -> String {
+    match result {
+        Err(TiledError::Unsupported(feature)) => feature,
+        other => panic!("expected an unsupported feature, got {:?}", other.err())
+    }
+}
+
+#[test]
+fn color_properties_become_the_nearest_foreground() {
+    let dir = Path::new("");
+    let map = |color: &str| format!(r#"<map orientation="orthogonal" width="1" height="1" tilewidth="8" tileheight="8">
+        <tileset firstgid="1" name="items" tilewidth="8" tileheight="8" tilecount="1" columns="0">
+            <tile id="0"><properties><property name="glyph" value="@"/>{}</properties></tile>
+        </tileset>
+        <layer name="ground"><data encoding="csv">1</data></layer>
+    </map>"#, color);
+    let glyph = |color: &str| loader().parse_tmx(&map(color), dir).unwrap().map.tile(1).unwrap().graphic;
+    assert_eq!(glyph(r##"<property name="color" type="color" value="#ffff0000"/>"##), TileGraphic::Glyph('@', RustConsole::FG_RED));
+    assert_eq!(glyph(r##"<property name="color" type="color" value="#008000"/>"##), TileGraphic::Glyph('@', RustConsole::FG_DARK_GREEN));
+    assert_eq!(glyph(""), TileGraphic::Glyph('@', RustConsole::FG_WHITE));
+    for color in &[r#"<property name="color" value="red"/>"#, r#"<property name="color" type="int" value="70000"/>"#, r#"<property name="color" type="bool" value="true"/>"#] {
+        match loader().parse_tmx(&map(color), dir) {
+            Err(TiledError::Format(message)) => assert!(message.starts_with("tile 0 of tileset 'items' has an invalid color"), "{}", message),
+            other => panic!("expected a format error, got {:?}", other.err())
+        }
+    }
+}
+
+#[test]
+fn unsupported_maps_are_rejected() {
+    let dir = Path::new("");
+    let isometric = r#"<map orientation="isometric" width="1" height="1" tilewidth="16" tileheight="8" infinite="0"/>"#;
+    assert!(unsupported(loader().parse_tmx(isometric, dir)).starts_with("isometric maps"));
+    let infinite = r#"{"orientation": "orthogonal", "infinite": true, "width": 1, "height": 1, "tilewidth": 8, "tileheight": 8, "layers": []}"#;
+    assert!(unsupported(loader().parse_tmj(infinite, dir)).starts_with("infinite maps"));
+    let staggered = r#"{"orientation": "staggered", "width": 1, "height": 1, "tilewidth": 8, "tileheight": 8, "layers": []}"#;
+    assert!(unsupported(loader().parse_tmj(staggered, dir)).starts_with("staggered maps"));
+    let zstd = r#"<map orientation="orthogonal" width="1" height="1" tilewidth="8" tileheight="8">
+        <layer name="ground"><data encoding="base64" compression="zstd">AAAA</data></layer>
+    </map>"#;
+    assert_eq!(unsupported(loader().parse_tmx(zstd, dir)), "zstd compressed layers");
+    assert!(matches!(loader().load(&asset("terrain.tsx")), Err(TiledError::Unsupported(_))));
+}
+
+#[test]
+fn broken_maps_are_format_errors() {
+    let dir = Path::new("");
+    assert!(matches!(loader().parse_tmj("{", dir), Err(TiledError::Format(_))));
+    assert!(matches!(loader().parse_tmx("<map", dir), Err(TiledError::Format(_))));
+    let short = r#"<map orientation="orthogonal" width="2" height="2" tilewidth="8" tileheight="8">
+        <layer name="ground"><data encoding="csv">0,0,0</data></layer>
+    </map>"#;
+    match loader().parse_tmx(short, dir) {
+        Err(TiledError::Format(message)) => assert_eq!(message, "layer 'ground' has 3 tiles instead of 4"),
+        other => panic!("expected a format error, got {:?}", other.err())
+    }
+    let no_size = r#"{"orientation": "orthogonal", "layers": []}"#;
+    assert!(matches!(loader().parse_tmj(no_size, dir), Err(TiledError::Format(_))));
+    let huge = r#"<map orientation="orthogonal" width="100000" height="100000" tilewidth="8" tileheight="8"/>"#;
+    assert!(matches!(loader().parse_tmx(huge, dir), Err(TiledError::Format(_))));
+    assert!(matches!(loader().load(&asset("missing.tmx")), Err(TiledError::Io(_))));
+}
+
+#[test]
+fn file_sizes_are_not_trusted() {
+    let dir = Path::new("");
+    // the data of a 2x2 map inflates to 4 tile ids, 5 is more than the map can hold
+    let layer = |data: &str| format!(r#"<map orientation="orthogonal" width="2" height="2" tilewidth="8" tileheight="8">
+        <layer name="ground"><data encoding="base64" compression="zlib">{}</data></layer>
+    </map>"#, data);
+    assert!(loader().parse_tmx(&layer("eJxjYEAFAAAQAAE="), dir).is_ok());
+    match loader().parse_tmx(&layer("eJxjYMAEAAAUAAE="), dir) {
+        Err(TiledError::Format(message)) => assert_eq!(message, "compressed layer data is larger than the map"),
+        other => panic!("expected a format error, got {:?}", other.err())
+    }
+
+    // only the tiles the sheet has cells for are defined, whatever the tile count says
+    let huge = r#"<map orientation="orthogonal" width="1" height="1" tilewidth="8" tileheight="8">
+        <tileset firstgid="1" name="terrain" tilewidth="8" tileheight="8" tilecount="16000000" columns="2"/>
+        <layer name="ground"><data encoding="csv">1</data></layer>
+    </map>"#;
+    let level = loader().parse_tmx(huge, dir).unwrap();
+    assert_eq!(level.map.tile(4).unwrap().graphic, TileGraphic::Tileset { tileset: 0, x: 2, y: 1 });
+    assert_eq!(level.map.tile(5), None);
+    assert!(matches!(TiledLoader::new(2, 1).parse_tmx(huge, dir), Err(TiledError::MissingSheet(_))));
+}
@@ -0,0 +1,88 @@
+use rust_console_game_engine::{DrawTarget, DrawTileMap, RustConsole, RustConsoleSprite, Tile, TileGraphic, TileMap};
+
+fn map() -> TileMap {
+    let mut map = TileMap::new(4, 3, 1, 1);
+    map.define_tile(1, Tile::glyph('.', RustConsole::FG_GREEN));
+    map.define_tile(2, Tile::glyph('~', RustConsole::FG_BLUE).with_flags(Tile::WATER));
+    map.define_tile(3, Tile::glyph('#', RustConsole::FG_GREY).with_flags(Tile::SOLID | Tile::USER));
+    let ground = map.add_layer("ground");
+    let walls = map.add_layer("walls");
+    map.layer_mut(ground).unwrap().fill(1);
+    map.layer_mut(ground).unwrap().set(1, 1, 2);
+    map.layer_mut(walls).unwrap().set(1, 1, 3);
+    map.layer_mut(walls).unwrap().set(3, 2, 3);
+    map
+}
+
+fn rows(sprite: &RustConsoleSprite) -> Vec<String> {
+    (0..sprite.height()).map(|y| (0..sprite.width()).map(|x| sprite.get_glyph(x, y)).collect()).collect()
+}
+
+#[test]
+fn layers_hold_tile_ids() {
+    let mut map = map();
+    assert_eq!((map.width(), map.height(), map.tile_width(), map.tile_height()), (4, 3, 1, 1));
+    assert_eq!(map.layers().len(), 2);
+    assert_eq!(map.find_layer("walls"), Some(1));
+    assert_eq!(map.find_layer("roof"), None);
+    let walls = map.layer_mut(1).unwrap();
+    assert_eq!(walls.name(), "walls");
+    assert_eq!(walls.get(1, 1), 3);
+    assert_eq!(walls.get(0, 0), 0);
+    walls.set(-1, 0, 3);
+    walls.set(4, 0, 3);
+    assert_eq!((walls.get(-1, 0), walls.get(4, 0), walls.get(0, 3)), (0, 0, 0));
+
+    map.define_tile(0, Tile::glyph('x', RustConsole::FG_RED));
+    assert_eq!(map.tile(0), None);
+    assert_eq!(map.tile(2).unwrap().graphic, TileGraphic::Glyph('~', RustConsole::FG_BLUE));
+}
+
+#[test]
+fn flags_combine_across_layers() {
+    let mut map = map();
+    assert_eq!(map.flags_at(0, 0), 0);
+    assert_eq!(map.flags_at(1, 1), Tile::WATER | Tile::SOLID | Tile::USER);
+    assert!(map.tile(3).unwrap().has(Tile::SOLID | Tile::USER));
+    assert!(!map.tile(3).unwrap().has(Tile::SOLID | Tile::WATER));
+    assert_eq!(map.flags_at(-1, 1), 0);
+    // hidden layers still collide
+    map.layer_mut(1).unwrap().visible = false;
+    assert_eq!(map.flags_at(3, 2), Tile::SOLID | Tile::USER);
+}
+
+#[test]
+fn draws_the_visible_layers_with_fractional_scroll() {
+    let mut map = map();
+    let mut target = RustConsoleSprite::new(5, 3).unwrap();
+    target.draw_tilemap(&map, 0f32, 0f32);
+    assert_eq!(rows(&target), vec![".... ", ".#.. ", "...# "]);
+
+    map.layer_mut(1).unwrap().visible = false;
+    let mut target = RustConsoleSprite::new(5, 3).unwrap();
+    // tiles of one cell can't show less than a cell, the fraction is dropped
+    target.draw_tilemap(&map, -1f32, 1.5f32);
+    assert_eq!(rows(&target), vec![" .~..", " ....", "     "]);
+}
+
+#[test]
+fn tileset_tiles_are_cut_at_the_tile_size() {
+    let mut tileset = RustConsoleSprite::new(4, 2).unwrap();
+    tileset.draw_string(0, 0, "ab c", RustConsole::FG_WHITE);
+    tileset.draw_string(0, 1, "de f", RustConsole::FG_WHITE);
+    let mut map = TileMap::new(3, 1, 2, 2);
+    let index = map.add_tileset(tileset);
+    assert_eq!(map.tileset(index).unwrap().width(), 4);
+    map.define_tile(1, Tile::tileset(index, 0, 0));
+    map.define_tile(2, Tile::tileset(index, 2, 0));
+    // a tileset that doesn't exist draws nothing
+    map.define_tile(3, Tile::tileset(index + 1, 0, 0));
+    let layer = map.add_layer("tiles");
+    for (x, id) in [1, 2, 3].iter().enumerate() {
+        map.layer_mut(layer).unwrap().set(x as i32, 0, *id);
+    }
+    let mut target = RustConsoleSprite::new(6, 2).unwrap();
+    target.fill(0, 0, 6, 2, '.', RustConsole::FG_GREY);
+    target.draw_tilemap(&map, 0.5f32, 0f32);
+    assert_eq!(rows(&target), vec!["b.c...", "e.f..."]);
+}
@@ -0,0 +1,33 @@
+use rust_console_game_engine::Transform2D;
+
+use std::f32::consts::PI;
+
+fn assert_close(actual: (f32, f32), expected: (f32, f32)) {
+    assert!((actual.0 - expected.0).abs() < 1e-4 && (actual.1 - expected.1).abs() < 1e-4, "{:?} != {:?}", actual, expected);
+}
+
+#[test]
+fn transforms_apply_in_order() {
+    let transform = Transform2D::translation(1f32, 0f32).rotate(PI / 2f32).scale(2f32, 3f32).translate(10f32, 20f32);
+    // (1, 0) -> (2, 0) -> (0, 2) -> (0, 6) -> (10, 26)
+    assert_close(transform.apply(1f32, 0f32), (10f32, 26f32));
+    assert_close(Transform2D::shearing(0.5f32, 0f32).apply(2f32, 4f32), (4f32, 4f32));
+    assert_eq!(Transform2D::default(), Transform2D::identity());
+}
+
+#[test]
+fn inverse_undoes_the_transform() {
+    let transform = Transform2D::rotation(0.7f32).shear(0.3f32, -0.2f32).scale(1.5f32, 0.5f32).translate(-4f32, 9f32);
+    let inverse = transform.inverse().unwrap();
+    for &(x, y) in &[(0f32, 0f32), (3f32, -2f32), (-7.5f32, 12.25f32)] {
+        assert_close(inverse.apply(transform.apply(x, y).0, transform.apply(x, y).1), (x, y));
+        assert_close(transform.then(&inverse).apply(x, y), (x, y));
+    }
+}
+
+#[test]
+fn degenerate_transforms_have_no_inverse() {
+    assert_eq!(Transform2D::scaling(0f32, 1f32).inverse(), None);
+    assert_eq!(Transform2D::scaling(2f32, 3f32).shear(1f32, 1f32).scale(1f32, 0f32).inverse(), None);
+    assert_eq!(Transform2D::scaling(f32::NAN, 1f32).inverse(), None);
+}
@@ -0,0 +1,131 @@
+use rust_console_game_engine::{DrawTarget, RustConsole, RustConsoleSprite, SpriteError};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+use std::io::{Read, Write};
+
+// one xp cell, column by column like the file
+type Cell = (u32, [u8; 3], [u8; 3]);
+
+const CLEAR: Cell = (0, [0, 0, 0], [255, 0, 255]);
+
+fn xp(layers: &[(i32, i32, Vec<Cell>)]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&(-1i32).to_le_bytes());
+    bytes.extend_from_slice(&(layers.len() as i32).to_le_bytes());
+    for (width, height, cells) in layers {
+        bytes.extend_from_slice(&width.to_le_bytes());
+        bytes.extend_from_slice(&height.to_le_bytes());
+        for (code, fg, bg) in cells {
+            bytes.extend_from_slice(&code.to_le_bytes());
+            bytes.extend_from_slice(fg);
+            bytes.extend_from_slice(bg);
+        }
+    }
+    gzip(&bytes)
+}
+
+fn gzip(bytes: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(bytes).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn rows(sprite: &RustConsoleSprite) -> Vec<String> {
+    (0..sprite.height()).map(|y| (0..sprite.width()).map(|x| sprite.get_glyph(x, y)).collect()).collect()
+}
+
+// a 2x2 image, the bottom layer full, the top one with a single cell and off palette colours
+fn two_layers() -> Vec<u8> {
+    let bottom = vec![
+        (1, [255, 255, 255], [0, 0, 128]),
+        (b'a' as u32, [0, 255, 0], [0, 0, 0]),
+        (219, [250, 10, 10], [0, 0, 0]),
+        (176, [120, 130, 120], [200, 190, 200])
+    ];
+    let top = vec![CLEAR, CLEAR, CLEAR, (0, [0, 0, 0], [0, 120, 0])];
+    xp(&[(2, 2, bottom), (2, 2, top)])
+}
+
+#[test]
+fn layers_are_flattened_with_cp437_glyphs_and_nearest_colours() {
+    let sprite = RustConsoleSprite::from_xp_reader(&two_layers()[..]).unwrap();
+    assert_eq!(rows(&sprite), vec!["☺█", "a "]);
+    assert_eq!(sprite.get_color(0, 0), RustConsole::FG_WHITE | RustConsole::BG_DARK_BLUE);
+    assert_eq!(sprite.get_color(1, 0), RustConsole::FG_RED);
+    assert_eq!(sprite.get_color(0, 1), RustConsole::FG_GREEN);
+    // the blank cell of the top layer is opaque, its background covers the shade below
+    assert_eq!(sprite.get_color(1, 1), RustConsole::BG_DARK_GREEN);
+}
+
+#[test]
+fn layers_can_be_kept_apart() {
+    let layers = RustConsoleSprite::xp_layers_from_reader(&two_layers()[..]).unwrap();
+    assert_eq!(layers.len(), 2);
+    assert_eq!(rows(&layers[0]), vec!["☺█", "a░"]);
+    assert_eq!(layers[0].get_color(1, 1), RustConsole::FG_DARK_GREY | RustConsole::BG_GREY);
+    assert_eq!(rows(&layers[1]), vec!["  ", "  "]);
+    assert_eq!(layers[1].get_color(0, 0), 0);
+    assert_eq!(layers[1].get_color(1, 1), RustConsole::BG_DARK_GREEN);
+}
+
+#[test]
+fn sprites_round_trip() {
+    let mut sprite = RustConsoleSprite::new(3, 2).unwrap();
+    sprite.draw_string(0, 0, "╔═♥", RustConsole::FG_YELLOW | RustConsole::BG_DARK_RED);
+    sprite.draw_string(0, 1, "é▒~", RustConsole::FG_CYAN | RustConsole::BG_DARK_MAGENTA);
+    let mut bytes = Vec::new();
+    sprite.write_xp(&mut bytes).unwrap();
+    let read = RustConsoleSprite::from_xp_reader(&bytes[..]).unwrap();
+    assert_eq!(rows(&read), rows(&sprite));
+    for (x, y) in (0..3).flat_map(|x| (0..2).map(move |y| (x, y))) {
+        assert_eq!(read.get_color(x, y), sprite.get_color(x, y));
+    }
+
+    // cells are stored column by column, with the palette's own colours
+    let mut raw = Vec::new();
+    GzDecoder::new(&bytes[..]).read_to_end(&mut raw).unwrap();
+    assert_eq!(raw.len(), 16 + 6 * 10);
+    assert_eq!(&raw[16..26], &[0xc9, 0, 0, 0, 255, 255, 0, 128, 0, 0]);
+    assert_eq!(raw[26], 0x82);
+}
+
+#[test]
+fn blank_cells_above_the_first_layer_are_saved_as_transparent() {
+    let mut bottom = RustConsoleSprite::new(2, 1).unwrap();
+    bottom.draw_string(0, 0, "##", RustConsole::FG_GREEN);
+    let mut top = RustConsoleSprite::new(2, 1).unwrap();
+    top.draw(1, 0, '@', RustConsole::FG_WHITE);
+    let mut bytes = Vec::new();
+    RustConsoleSprite::write_xp_layers(&[bottom, top], &mut bytes).unwrap();
+    let sprite = RustConsoleSprite::from_xp_reader(&bytes[..]).unwrap();
+    assert_eq!(rows(&sprite), vec!["#@"]);
+    assert_eq!(sprite.get_color(0, 0), RustConsole::FG_GREEN);
+}
+
+#[test]
+fn broken_files_are_rejected() {
+    let mut bytes = two_layers();
+    let mut raw = Vec::new();
+    GzDecoder::new(&bytes[..]).read_to_end(&mut raw).unwrap();
+    match RustConsoleSprite::from_xp_reader(&gzip(&raw[..raw.len() - 3])[..]) {
+        Err(SpriteError::Truncated { expected, found }) => assert_eq!((expected, found), (raw.len(), raw.len() - 3)),
+        other => panic!("expected truncated data, got {:?}", other.err())
+    }
+    raw.push(0);
+    assert!(matches!(RustConsoleSprite::from_xp_reader(&gzip(&raw)[..]), Err(SpriteError::TrailingBytes)));
+
+    let wide_glyph = xp(&[(1, 1, vec![(300, [0, 0, 0], [0, 0, 0])])]);
+    assert!(matches!(RustConsoleSprite::from_xp_reader(&wide_glyph[..]), Err(SpriteError::InvalidGlyph { x: 0, y: 0, code: 300 })));
+    let mismatched = xp(&[(1, 1, vec![CLEAR]), (1, 2, vec![CLEAR, CLEAR])]);
+    assert!(matches!(RustConsoleSprite::from_xp_reader(&mismatched[..]), Err(SpriteError::Format(_))));
+    let huge = xp(&[(100_000, 100_000, vec![])]);
+    assert!(matches!(RustConsoleSprite::from_xp_reader(&huge[..]), Err(SpriteError::TooLarge { .. })));
+    bytes.truncate(4);
+    assert!(RustConsoleSprite::from_xp_reader(&bytes[..]).is_err());
+
+    let mut star = RustConsoleSprite::new(2, 1).unwrap();
+    star.draw(1, 0, '★', RustConsole::FG_YELLOW);
+    assert!(matches!(star.write_xp(Vec::new()), Err(SpriteError::InvalidGlyph { x: 1, y: 0, code: 0x2605 })));
+    assert!(matches!(RustConsoleSprite::write_xp_layers(&[], Vec::new()), Err(SpriteError::Format(_))));
+}